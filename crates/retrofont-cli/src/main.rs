@@ -1,15 +1,20 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use retrofont::{
-    convert::figlet_to_tdf,
+    bundle::TdfBundle,
+    colorize::{CellColors, ColorFill, Colorizer, GradientDirection},
+    convert::{convert, ConvertOptions, FontKind, GlyphFit},
+    cp437::{Cp437Options, UnmappablePolicy},
+    effects::{Border, Extrusion, Shadow},
     figlet::FigletFont,
-    tdf::{TdfFont, TdfFontType},
-    Font, RenderOptions,
+    output::{render_canvas, Encoding, OutputFormat, OutputOptions},
+    tdf::TdfFont,
+    validate::{validate, Severity},
+    Canvas, Color, Font, FontStack, Palette, RenderOptions, Rgb, VerticalAlign,
 };
 use std::fs;
-
-use crate::console::render_to_ansi;
-mod console;
+use std::io::Write;
+use std::path::Path;
 
 fn validate_outline_style(s: &str) -> Result<usize, String> {
     let value: usize = s
@@ -27,6 +32,54 @@ fn validate_outline_style(s: &str) -> Result<usize, String> {
     }
 }
 
+fn parse_fit(s: &str) -> Result<GlyphFit, String> {
+    GlyphFit::from_name(s)
+        .ok_or_else(|| format!("unknown fit mode '{s}' (drop, crop, downscale or split)"))
+}
+
+fn parse_remap(s: &str) -> Result<(u8, u8), String> {
+    let (from, to) = s
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' is not FROM=TO", s))?;
+    let index = |v: &str| -> Result<u8, String> {
+        v.trim()
+            .parse::<u8>()
+            .ok()
+            .filter(|i| *i < 16)
+            .ok_or_else(|| format!("'{}' is not a color index (0-15)", v))
+    };
+    Ok((index(from)?, index(to)?))
+}
+
+fn parse_rgb(s: &str) -> Result<Rgb, String> {
+    Rgb::from_hex(s.trim()).ok_or_else(|| format!("'{}' is not a #RRGGBB color", s))
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    Color::parse(s).ok_or_else(|| {
+        format!(
+            "'{}' is not a color (0-15, #RRGGBB or one of: {})",
+            s,
+            Color::NAMES.join(", ")
+        )
+    })
+}
+
+fn load_palette(spec: &str) -> Result<Palette> {
+    if let Some(palette) = Palette::by_name(spec) {
+        return Ok(palette);
+    }
+    let text = fs::read_to_string(spec).map_err(|e| {
+        anyhow::anyhow!(
+            "'{}' is neither a built-in palette ({}) nor a readable file: {}",
+            spec,
+            Palette::BUILTIN_NAMES.join(", "),
+            e
+        )
+    })?;
+    Ok(Palette::parse(spec, &text)?)
+}
+
 #[derive(Parser)]
 #[command(name = "retrofont", about = "Retro font toolkit CLI")]
 struct Cli {
@@ -35,6 +88,62 @@ struct Cli {
 }
 const OUTLINE_STYLE_COUNT: usize = 19;
 
+/// Glyph placement and fallback fonts.
+#[derive(Args)]
+struct LayoutArgs {
+    #[arg(
+        long,
+        default_value = "top",
+        help = "Vertical glyph alignment: top (as TheDraw) or baseline (for fonts with short lowercase letters and punctuation)."
+    )]
+    align: String,
+    #[arg(
+        long,
+        help = "Font to take missing characters from; repeat for a chain. TDF bundles use their first font."
+    )]
+    fallback: Vec<String>,
+    #[arg(
+        long,
+        requires = "fallback",
+        help = "Scale fallback glyphs up (2x or 4x) to match the cap height of --font."
+    )]
+    scale_fallback: bool,
+}
+
+/// Effects drawn around the rendered text, in this order.
+#[derive(Args)]
+struct EffectArgs {
+    #[arg(
+        long,
+        help = "Trace the text with an outline in this style (0..18).",
+        value_parser = validate_outline_style
+    )]
+    border: Option<usize>,
+    #[arg(long, value_parser = parse_color, requires = "border", help = "Color of the --border outline.")]
+    border_color: Option<Color>,
+    #[arg(long, help = "Extrude the text into this many 3D layers.")]
+    extrude: Option<usize>,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_parser = parse_color,
+        requires = "extrude",
+        help = "Colors of the --extrude layers from front to back, e.g. 'light-gray,dark-gray'."
+    )]
+    extrude_color: Vec<Color>,
+    #[arg(long, help = "Add a drop shadow behind the text.")]
+    shadow: bool,
+    #[arg(
+        long,
+        default_value = "░",
+        requires = "shadow",
+        help = "Shadow character, e.g. ░, ▒, ▓ or █ for a solid shadow."
+    )]
+    shadow_char: char,
+    #[arg(long, value_parser = parse_color, requires = "shadow", help = "Shadow color.")]
+    shadow_color: Option<Color>,
+}
+
 #[derive(Subcommand)]
 enum Cmd {
     /// Render text with a font
@@ -43,10 +152,24 @@ enum Cmd {
         font: String,
         #[arg(short, long)]
         text: String,
-        #[arg(long, default_value = "7")]
-        fg: u8,
-        #[arg(long, default_value = "0")]
-        bg: u8,
+        #[arg(
+            long,
+            value_parser = parse_color,
+            conflicts_with_all = ["filter", "gradient"],
+            help = "Foreground color for fonts without colors: index 0-15, name (e.g. light-red) or #RRGGBB."
+        )]
+        fg: Option<Color>,
+        #[arg(
+            long,
+            value_parser = parse_color,
+            help = "Background color for fonts without colors: index 0-15, name (e.g. blue) or #RRGGBB. Bright backgrounds (8-15) need --ice."
+        )]
+        bg: Option<Color>,
+        #[arg(
+            long,
+            help = "Apply --fg/--bg to color fonts too, replacing their own colors."
+        )]
+        override_colors: bool,
         #[arg(
             long,
             default_value = "0",
@@ -56,6 +179,13 @@ enum Cmd {
         outline: usize,
         #[arg(long)]
         edit: bool,
+        #[command(flatten)]
+        layout: Box<LayoutArgs>,
+        #[arg(
+            long,
+            help = "iCE colors: use the blink bit as high-intensity background (16 background colors)."
+        )]
+        ice: bool,
         #[arg(
             short,
             long,
@@ -63,15 +193,68 @@ enum Cmd {
             help = "Font number in TDF bundle (1-based). Use 'inspect' to see available fonts."
         )]
         num: usize,
+        #[arg(
+            long,
+            default_value = "ansi",
+            help = "Output format: ansi, pcboard, pipe, ctrl-a or avatar."
+        )]
+        format: String,
+        #[arg(
+            long,
+            help = "Output encoding: utf8 or cp437. Defaults to utf8 for ansi and cp437 for BBS formats."
+        )]
+        encoding: Option<String>,
+        #[arg(
+            long,
+            default_value = "dos",
+            help = "Palette for ANSI output: dos, ega, ega:<16 register values>, cga, amiga, c64 or a palette file (#RRGGBB lines or GIMP .gpl)."
+        )]
+        palette: String,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = parse_remap,
+            help = "Remap color indices, e.g. '4=2,12=10' draws red as green."
+        )]
+        remap: Vec<(u8, u8)>,
+        #[arg(
+            long,
+            help = "Colorize uncolored fonts with a preset: fire, ice, metal or rainbow."
+        )]
+        filter: Option<String>,
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_parser = parse_rgb,
+            conflicts_with = "filter",
+            help = "Colorize uncolored fonts with a gradient, e.g. '#FF0000,#0000FF' (one color is solid)."
+        )]
+        gradient: Vec<Rgb>,
+        #[arg(
+            long,
+            default_value = "horizontal",
+            help = "Gradient direction: horizontal, vertical or diagonal."
+        )]
+        direction: String,
+        #[arg(
+            long,
+            help = "Limit colorizing to the 16 palette colors instead of 24-bit RGB."
+        )]
+        quantize: bool,
+        #[command(flatten)]
+        effects: Box<EffectArgs>,
     },
-    /// Convert FIGlet (.flf) to TDF
+    /// Convert between FIGlet (.flf), TOIlet (.tlf) and TDF block, outline and color fonts
     Convert {
         #[arg(short, long)]
         input: String,
         #[arg(short, long)]
         output: String,
-        #[arg(long, default_value = "color")]
-        ty: String,
+        #[arg(
+            long,
+            help = "Target type: figlet, tlf, block, outline or color. Defaults to the output extension (.flf, .tlf; color for .tdf)."
+        )]
+        ty: Option<String>,
         #[arg(
             short,
             long,
@@ -79,12 +262,143 @@ enum Cmd {
             help = "Font number in TDF bundle to convert (1-based). Use 'inspect' to see available fonts."
         )]
         num: usize,
+        #[arg(
+            long,
+            default_value = "0",
+            help = "Outline style (0..18) baked in when converting TDF outline fonts to FIGlet, block or color.",
+            value_parser = validate_outline_style
+        )]
+        outline: usize,
+        #[arg(
+            long,
+            default_value = "drop",
+            help = "Glyphs over the TDF limits (30x12): drop, crop, downscale (half/quarter blocks) or split (bundle of row bands).",
+            value_parser = parse_fit
+        )]
+        fit: GlyphFit,
+        #[arg(
+            long,
+            help = "Fail on characters without a CP437 equivalent instead of writing '?' (TDF output)."
+        )]
+        strict: bool,
     },
     /// Inspect font metadata
     Inspect {
         #[arg(short, long)]
         font: String,
     },
+    /// Check fonts for broken glyph data
+    Lint {
+        #[arg(required = true)]
+        fonts: Vec<String>,
+        #[arg(long, help = "Also fail on warnings.")]
+        deny_warnings: bool,
+    },
+    /// Edit TDF bundles; fonts are picked by number (1-based) or name
+    Bundle {
+        #[command(subcommand)]
+        op: BundleOp,
+    },
+}
+
+#[derive(Subcommand)]
+enum BundleOp {
+    /// List the fonts of a bundle
+    List { bundle: String },
+    /// Add the fonts of other TDF files
+    Add {
+        bundle: String,
+        #[arg(required = true)]
+        fonts: Vec<String>,
+    },
+    /// Remove a font
+    Remove { bundle: String, font: String },
+    /// Rename a font (12 bytes at most)
+    Rename {
+        bundle: String,
+        font: String,
+        name: String,
+    },
+    /// Move a font to another position (1-based)
+    Move {
+        bundle: String,
+        font: String,
+        to: usize,
+    },
+    /// Replace a font with the first font of another TDF file
+    Replace {
+        bundle: String,
+        font: String,
+        with: String,
+    },
+    /// Salvage the readable fonts of a damaged bundle
+    Recover { bundle: String, output: String },
+    /// Write every font to its own file in a directory
+    Split {
+        bundle: String,
+        #[arg(long, default_value = ".")]
+        dir: String,
+    },
+}
+
+/// Index of a font given by 1-based number or name.
+fn find_font(bundle: &TdfBundle, font: &str) -> Result<usize> {
+    if let Ok(num) = font.parse::<usize>() {
+        if num == 0 || num > bundle.len() {
+            anyhow::bail!(
+                "Font #{} does not exist. Bundle contains {} font(s).",
+                num,
+                bundle.len()
+            );
+        }
+        return Ok(num - 1);
+    }
+    bundle
+        .position(font)
+        .ok_or_else(|| anyhow::anyhow!("No font named '{font}' in bundle"))
+}
+
+fn edit_bundle(path: &str, edit: impl FnOnce(&mut TdfBundle) -> Result<()>) -> Result<()> {
+    let mut bundle = TdfBundle::load_file(Path::new(path))?;
+    edit(&mut bundle)?;
+    fs::write(path, bundle.to_bytes()?)?;
+    Ok(())
+}
+
+/// Load font number `num` (1-based) from a FIGlet file or TDF bundle.
+fn load_font(path: &str, num: usize) -> Result<Font> {
+    // crude format detection
+    if path.ends_with(".flf") || path.ends_with(".tlf") {
+        if num > 1 {
+            anyhow::bail!("FIGlet files contain only one font, --num must be 1");
+        }
+        return Ok(Font::Figlet(FigletFont::load_file(Path::new(path))?));
+    }
+    let fonts = TdfFont::load_file(Path::new(path))?;
+    let font_count = fonts.len();
+    if font_count == 0 {
+        anyhow::bail!("No fonts found in TDF file");
+    }
+    if num > font_count {
+        anyhow::bail!(
+            "Font #{} does not exist. TDF bundle contains {} font(s). Use 'inspect' to list available fonts.",
+            num,
+            font_count
+        );
+    }
+    Ok(Font::Tdf(fonts.into_iter().nth(num - 1).unwrap()))
+}
+
+fn print_metrics(font: &Font) {
+    let m = font.metrics();
+    println!(
+        "  Height: {}, baseline: {}, cap height: {}, x-height: {}, descender: {}",
+        m.height, m.baseline, m.cap_height, m.x_height, m.descender
+    );
+    println!(
+        "  Advance: {:.1} average, {} max",
+        m.average_advance, m.max_advance
+    );
 }
 
 fn main() -> Result<()> {
@@ -94,9 +408,22 @@ fn main() -> Result<()> {
             font,
             text,
             edit,
+            layout,
+            ice,
             outline,
             num,
-            ..
+            format,
+            encoding,
+            palette,
+            remap,
+            filter,
+            gradient,
+            direction,
+            quantize,
+            fg,
+            bg,
+            override_colors,
+            effects,
         } => {
             // Extra defensive check (in case future changes bypass clap range)
             if outline >= OUTLINE_STYLE_COUNT {
@@ -111,36 +438,135 @@ fn main() -> Result<()> {
                 anyhow::bail!("Font number must be 1 or greater (1-based index)");
             }
 
-            let bytes = fs::read(&font)?;
+            let format = OutputFormat::from_name(&format).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown output format '{}' (valid: ansi, pcboard, pipe, ctrl-a, avatar)",
+                    format
+                )
+            })?;
+            let encoding = match encoding {
+                Some(name) => Encoding::from_name(&name).ok_or_else(|| {
+                    anyhow::anyhow!("Unknown encoding '{}' (valid: utf8, cp437)", name)
+                })?,
+                None => format.default_encoding(),
+            };
+            let palette = load_palette(&palette)?;
+            let fill = match (filter, gradient.len()) {
+                (Some(name), _) => Some(ColorFill::preset(&name).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown filter '{}' (valid: {})",
+                        name,
+                        ColorFill::PRESET_NAMES.join(", ")
+                    )
+                })?),
+                (None, 0) => None,
+                (None, 1) => Some(ColorFill::Solid(gradient[0])),
+                (None, _) => Some(ColorFill::Gradient {
+                    direction: GradientDirection::from_name(&direction).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Unknown direction '{}' (valid: horizontal, vertical, diagonal)",
+                            direction
+                        )
+                    })?,
+                    stops: gradient,
+                }),
+            };
+
             let mut mode = if edit {
                 RenderOptions::edit()
             } else {
                 RenderOptions::default()
             };
             mode.outline_style = outline;
-            // crude format detection
-            let font_enum = if font.ends_with(".flf") {
-                if num > 1 {
-                    anyhow::bail!("FIGlet files contain only one font, --num must be 1");
+            mode.ice_colors = ice;
+            let LayoutArgs {
+                align,
+                fallback,
+                scale_fallback,
+            } = *layout;
+            mode.align = VerticalAlign::from_name(&align).ok_or_else(|| {
+                anyhow::anyhow!("Unknown alignment '{}' (valid: top, baseline)", align)
+            })?;
+            for (from, to) in remap {
+                mode = mode.remap_color(from, to);
+            }
+            let mut stack = FontStack::new(load_font(&font, num)?);
+            for path in &fallback {
+                stack.push(load_font(path, 1)?);
+            }
+            stack.scale_fallbacks = scale_fallback;
+            let mut canvas = Canvas::render_stack(&stack, &text, &mode)?;
+            CellColors {
+                fg,
+                bg,
+                override_colored: override_colors,
+                ice_colors: ice,
+            }
+            .apply(&mut canvas, &palette);
+            if let Some(fill) = fill {
+                Colorizer {
+                    fill,
+                    true_color: !quantize,
+                    palette: palette.clone(),
                 }
-                Font::Figlet(FigletFont::load(&bytes)?)
-            } else {
-                let fonts = TdfFont::load(&bytes)?;
-                let font_count = fonts.len();
-                if font_count == 0 {
-                    anyhow::bail!("No fonts found in TDF file");
-                }
-                if num > font_count {
-                    anyhow::bail!(
-                        "Font #{} does not exist. TDF bundle contains {} font(s). Use 'inspect' to list available fonts.",
-                        num,
-                        font_count
-                    );
+                .apply(&mut canvas);
+            }
+            let EffectArgs {
+                border,
+                border_color,
+                extrude,
+                extrude_color,
+                shadow,
+                shadow_char,
+                shadow_color,
+            } = *effects;
+            if let Some(style) = border {
+                Border {
+                    style,
+                    color: border_color,
                 }
-                Font::Tdf(fonts.into_iter().nth(num - 1).unwrap())
-            };
-            let ansi = render_to_ansi(&font_enum, &text, &mode)?;
-            println!("{ansi}");
+                .apply(&mut canvas, &palette);
+            }
+            if let Some(depth) = extrude {
+                let mut extrusion = Extrusion {
+                    depth,
+                    ..Extrusion::default()
+                };
+                if !extrude_color.is_empty() {
+                    extrusion.colors = extrude_color;
+                }
+                extrusion.apply(&mut canvas, &palette);
+            }
+            if shadow {
+                Shadow {
+                    ch: shadow_char,
+                    color: shadow_color,
+                    ..Shadow::default()
+                }
+                .apply(&mut canvas, &palette);
+            }
+            let output = render_canvas(
+                &canvas,
+                &OutputOptions {
+                    format,
+                    encoding,
+                    palette,
+                },
+            );
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(&output.bytes)?;
+            if format == OutputFormat::Ansi {
+                stdout.write_all(b"\n")?;
+            }
+            if !output.unencodable.is_empty() {
+                let chars: String = output.unencodable.iter().collect();
+                eprintln!(
+                    "Warning: {} character(s) cannot be encoded as {:?} and were written as '?': {}",
+                    output.unencodable.len(),
+                    encoding,
+                    chars
+                );
+            }
         }
 
         Cmd::Convert {
@@ -148,44 +574,97 @@ fn main() -> Result<()> {
             output,
             ty,
             num,
+            outline,
+            fit,
+            strict,
         } => {
             if num == 0 {
                 anyhow::bail!("Font number must be 1 or greater (1-based index)");
             }
+            let target = match ty {
+                Some(ty) => FontKind::from_name(&ty).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown font type '{ty}' (figlet, tlf, block, outline or color)"
+                    )
+                })?,
+                None => {
+                    let lower = output.to_ascii_lowercase();
+                    if lower.ends_with(".flf") {
+                        FontKind::Figlet
+                    } else if lower.ends_with(".tlf") {
+                        FontKind::Tlf
+                    } else {
+                        FontKind::TdfColor
+                    }
+                }
+            };
 
-            let bytes = fs::read(&input)?;
-
-            // Currently only FIGlet to TDF conversion is supported
-            if !input.ends_with(".flf") {
-                anyhow::bail!("Convert currently only supports FIGlet (.flf) input files");
+            let fonts = Font::load_file(Path::new(&input))?;
+            if num > fonts.len() {
+                anyhow::bail!(
+                    "Font #{} does not exist. {} contains {} font(s). Use 'inspect' to list available fonts.",
+                    num,
+                    input,
+                    fonts.len()
+                );
             }
-
-            if num > 1 {
-                anyhow::bail!("FIGlet files contain only one font, --num must be 1");
+            let conversion = convert(
+                &fonts[num - 1],
+                target,
+                &ConvertOptions {
+                    outline_style: outline,
+                    fit,
+                },
+            )?;
+            if !conversion.report.is_lossless() {
+                eprint!(
+                    "Warning: converting {} to {} was lossy:\n{}",
+                    FontKind::of(&fonts[num - 1]),
+                    target,
+                    conversion.report
+                );
             }
-
-            let fig = FigletFont::load(&bytes)?;
-            let target_type = match ty.to_lowercase().as_str() {
-                "outline" => TdfFontType::Outline,
-                "block" => TdfFontType::Block,
-                "color" => TdfFontType::Color,
-                _ => TdfFontType::Color,
+            let cp437 = Cp437Options {
+                policy: if strict {
+                    UnmappablePolicy::Error
+                } else {
+                    UnmappablePolicy::Substitute
+                },
+                ..Default::default()
             };
-            let tdf = figlet_to_tdf(&fig, target_type)?;
-            // placeholder serialization (real TDF writer TBD)
-            match tdf.to_bytes() {
-                Ok(bytes) => fs::write(&output, bytes)?,
-                Err(e) => eprintln!("Failed to convert TDF font to bytes: {e}"),
+            if conversion.split_fonts.is_empty() {
+                let bytes = match &conversion.font {
+                    Font::Tdf(tdf) => tdf.to_bytes_with(cp437)?.bytes,
+                    font => font.to_bytes()?,
+                };
+                fs::write(&output, bytes)?;
+            } else {
+                let parts: Vec<TdfFont> = std::iter::once(conversion.font)
+                    .chain(conversion.split_fonts)
+                    .filter_map(|f| match f {
+                        Font::Tdf(t) => Some(t),
+                        Font::Figlet(_) => None,
+                    })
+                    .collect();
+                eprintln!(
+                    "Split into {} fonts of up to 12 rows, render them line by line",
+                    parts.len()
+                );
+                fs::write(
+                    &output,
+                    TdfFont::serialize_bundle_with(&parts, cp437)?.bytes,
+                )?;
             }
         }
         Cmd::Inspect { font } => {
-            let bytes = fs::read(&font)?;
-            if font.ends_with(".flf") {
-                let f = FigletFont::load(&bytes)?;
+            if font.ends_with(".flf") || font.ends_with(".tlf") {
+                let f = FigletFont::load_file(Path::new(&font))?;
                 println!("FIGlet font: {}", f.name);
                 println!("  Defined characters: {}", f.glyph_count());
+                print_metrics(&Font::Figlet(f));
             } else {
-                let fonts = TdfFont::load(&bytes)?;
+                let bundle = TdfBundle::load_file(Path::new(&font))?;
+                let fonts = bundle.fonts();
                 let font_count = fonts.len();
                 if font_count > 1 {
                     println!("TDF bundle: {} fonts", font_count);
@@ -197,9 +676,123 @@ fn main() -> Result<()> {
                         println!("TDF font: {} ({:?})", f.name, f.font_type());
                     }
                     println!("  Defined characters: {}", f.glyph_count());
+                    print_metrics(&Font::Tdf(f.clone()));
+                }
+                if let Some(sauce) = bundle.sauce() {
+                    println!(
+                        "\nSAUCE: {} by {} / {} ({})",
+                        sauce.title, sauce.author, sauce.group, sauce.date
+                    );
+                }
+            }
+        }
+        Cmd::Lint {
+            fonts,
+            deny_warnings,
+        } => {
+            let fail_at = if deny_warnings {
+                Severity::Warning
+            } else {
+                Severity::Error
+            };
+            let mut failed = 0;
+            for path in &fonts {
+                let diagnostics = match validate(&fs::read(path)?) {
+                    Ok(diagnostics) => diagnostics,
+                    Err(e) => {
+                        println!("{path}: error: {e}");
+                        failed += 1;
+                        continue;
+                    }
+                };
+                for d in &diagnostics {
+                    println!("{path}: {d}");
+                }
+                if diagnostics.iter().any(|d| d.severity >= fail_at) {
+                    failed += 1;
                 }
             }
+            if failed > 0 {
+                anyhow::bail!("{failed} of {} font file(s) failed the check", fonts.len());
+            }
         }
+        Cmd::Bundle { op } => match op {
+            BundleOp::List { bundle } => {
+                let bundle = TdfBundle::load_file(Path::new(&bundle))?;
+                for (idx, f) in bundle.fonts().iter().enumerate() {
+                    println!(
+                        "{:>3}. {:<12} {:?}, {} characters",
+                        idx + 1,
+                        f.name,
+                        f.font_type(),
+                        f.glyph_count()
+                    );
+                }
+            }
+            BundleOp::Add { bundle, fonts } => edit_bundle(&bundle, |b| {
+                for path in fonts {
+                    b.merge(TdfBundle::load_file(Path::new(&path))?);
+                }
+                Ok(())
+            })?,
+            BundleOp::Remove { bundle, font } => edit_bundle(&bundle, |b| {
+                b.remove(find_font(b, &font)?)?;
+                Ok(())
+            })?,
+            BundleOp::Rename { bundle, font, name } => edit_bundle(&bundle, |b| {
+                b.rename(find_font(b, &font)?, name)?;
+                Ok(())
+            })?,
+            BundleOp::Move { bundle, font, to } => edit_bundle(&bundle, |b| {
+                if to == 0 || to > b.len() {
+                    anyhow::bail!("Position must be between 1 and {}", b.len());
+                }
+                b.move_font(find_font(b, &font)?, to - 1)?;
+                Ok(())
+            })?,
+            BundleOp::Replace { bundle, font, with } => edit_bundle(&bundle, |b| {
+                let Some(new) = TdfFont::load_file(Path::new(&with))?.into_iter().next() else {
+                    anyhow::bail!("No fonts found in {with}");
+                };
+                b.replace(find_font(b, &font)?, new)?;
+                Ok(())
+            })?,
+            BundleOp::Recover { bundle, output } => {
+                let recovered = TdfFont::recover(&fs::read(&bundle)?);
+                for skipped in &recovered.skipped {
+                    eprintln!(
+                        "Skipped bytes {:#x}..{:#x}: {}",
+                        skipped.range.start, skipped.range.end, skipped.reason
+                    );
+                }
+                for &idx in &recovered.truncated {
+                    eprintln!(
+                        "Font #{} ({}) is cut off, {} characters left",
+                        idx + 1,
+                        recovered.fonts[idx].name,
+                        recovered.fonts[idx].glyph_count()
+                    );
+                }
+                if recovered.fonts.is_empty() {
+                    anyhow::bail!("No fonts found in {bundle}");
+                }
+                fs::write(&output, TdfFont::serialize_bundle(&recovered.fonts)?)?;
+                println!("Recovered {} font(s)", recovered.fonts.len());
+            }
+            BundleOp::Split { bundle, dir } => {
+                let bundle = TdfBundle::load_file(Path::new(&bundle))?;
+                for (idx, part) in bundle.split().iter().enumerate() {
+                    let name: String = part.fonts()[0]
+                        .name
+                        .chars()
+                        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                        .collect();
+                    let path = Path::new(&dir).join(format!("{:02}_{name}.tdf", idx + 1));
+                    fs::write(&path, part.to_bytes()?)?;
+                    println!("{}", path.display());
+                }
+            }
+        },
     }
     Ok(())
 }
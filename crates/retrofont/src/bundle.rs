@@ -0,0 +1,243 @@
+//! TDF bundles: several TheDraw fonts in one file.
+//!
+//! [`TdfBundle`] keeps the fonts in file order together with everything stored after the
+//! bundle terminator, usually a SAUCE record, so editing a bundle doesn't lose its metadata.
+use std::{fs, path::Path, sync::Arc};
+
+use crate::{
+    cp437::Cp437Options,
+    error::{FontError, Result},
+    tdf::{SavedTdf, TdfFont, MAX_TDF_NAME_LEN},
+};
+
+const SAUCE_LEN: usize = 128;
+
+/// The SAUCE record of a file, see <https://www.acid.org/info/sauce/sauce.htm>.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sauce {
+    pub title: String,
+    pub author: String,
+    pub group: String,
+    /// `CCYYMMDD`
+    pub date: String,
+}
+
+impl Sauce {
+    /// Parse the SAUCE record at the end of `data`.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let record = data.get(data.len().checked_sub(SAUCE_LEN)?..)?;
+        if !record.starts_with(b"SAUCE00") {
+            return None;
+        }
+        let field = |range: std::ops::Range<usize>| {
+            String::from_utf8_lossy(&record[range])
+                .trim_end_matches(['\0', ' '])
+                .to_string()
+        };
+        Some(Self {
+            title: field(7..42),
+            author: field(42..62),
+            group: field(62..82),
+            date: field(82..90),
+        })
+    }
+}
+
+/// Whether `trailer`, the bytes after the fonts of a bundle, is what TDF files usually end
+/// with: nothing, or the bundle terminator and an EOF marker with an optional SAUCE record.
+pub(crate) fn is_known_trailer(trailer: &[u8]) -> bool {
+    // Single fonts have no terminator, bundles do.
+    let trailer = trailer.strip_prefix(&[0]).unwrap_or(trailer);
+    trailer.is_empty()
+        || trailer == [0x1A]
+        || (trailer[0] == 0x1A && Sauce::from_bytes(trailer).is_some())
+}
+
+/// An editable list of TDF fonts.
+#[derive(Clone, Default)]
+pub struct TdfBundle {
+    fonts: Vec<TdfFont>,
+    /// Bytes after the last font (EOF marker, SAUCE record), written back unchanged after the
+    /// bundle terminator.
+    pub trailer: Vec<u8>,
+}
+
+impl From<Vec<TdfFont>> for TdfBundle {
+    fn from(fonts: Vec<TdfFont>) -> Self {
+        Self {
+            fonts,
+            trailer: Vec::new(),
+        }
+    }
+}
+
+impl TdfBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(bytes: &[u8]) -> Result<Self> {
+        Self::load_arc(Arc::<[u8]>::from(bytes.to_vec()))
+    }
+
+    pub fn load_arc(bytes: Arc<[u8]>) -> Result<Self> {
+        let (fonts, end) = TdfFont::parse_bundle(bytes.clone())?;
+        // The terminator is written by `to_bytes`, an EOF marker belongs to the trailer.
+        let start = if bytes.get(end) == Some(&0) {
+            end + 1
+        } else {
+            end
+        };
+        Ok(Self {
+            fonts,
+            trailer: bytes.get(start..).unwrap_or_default().to_vec(),
+        })
+    }
+
+    /// Load a bundle file; errors name the file, see [`FontError::File`].
+    pub fn load_file(path: &Path) -> Result<Self> {
+        fs::read(path)
+            .map_err(FontError::from)
+            .and_then(|bytes| Self::load(&bytes))
+            .map_err(|e| e.in_file(path))
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(self.to_bytes_with(Cp437Options::default())?.bytes)
+    }
+
+    /// Serialize, mapping characters to CP437 according to `options`.
+    pub fn to_bytes_with(&self, options: Cp437Options) -> Result<SavedTdf> {
+        let mut saved = TdfFont::serialize_bundle_with(&self.fonts, options)?;
+        saved.bytes.extend(&self.trailer);
+        Ok(saved)
+    }
+
+    /// The SAUCE record from the trailer, if any.
+    pub fn sauce(&self) -> Option<Sauce> {
+        Sauce::from_bytes(&self.trailer)
+    }
+
+    pub fn len(&self) -> usize {
+        self.fonts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+
+    pub fn fonts(&self) -> &[TdfFont] {
+        &self.fonts
+    }
+
+    pub fn into_fonts(self) -> Vec<TdfFont> {
+        self.fonts
+    }
+
+    pub fn get(&self, index: usize) -> Option<&TdfFont> {
+        self.fonts.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut TdfFont> {
+        self.fonts.get_mut(index)
+    }
+
+    /// Index of the first font named `name`; exact matches win over case-insensitive ones.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.fonts.iter().position(|f| f.name == name).or_else(|| {
+            self.fonts
+                .iter()
+                .position(|f| f.name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// The first font named `name`, see [`TdfBundle::position`].
+    pub fn by_name(&self, name: &str) -> Option<&TdfFont> {
+        self.position(name).map(|i| &self.fonts[i])
+    }
+
+    /// Append a font, returning its index.
+    pub fn add(&mut self, font: TdfFont) -> usize {
+        self.fonts.push(font);
+        self.fonts.len() - 1
+    }
+
+    /// Insert a font at `index`, shifting the following fonts back.
+    pub fn insert(&mut self, index: usize, font: TdfFont) -> Result<()> {
+        if index > self.fonts.len() {
+            return Err(self.out_of_range(index));
+        }
+        self.fonts.insert(index, font);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<TdfFont> {
+        self.check(index)?;
+        Ok(self.fonts.remove(index))
+    }
+
+    /// Replace the font at `index`, returning the old one.
+    pub fn replace(&mut self, index: usize, font: TdfFont) -> Result<TdfFont> {
+        self.check(index)?;
+        Ok(std::mem::replace(&mut self.fonts[index], font))
+    }
+
+    /// Move the font at `from` so that it ends up at index `to`.
+    pub fn move_font(&mut self, from: usize, to: usize) -> Result<()> {
+        self.check(from)?;
+        self.check(to)?;
+        let font = self.fonts.remove(from);
+        self.fonts.insert(to, font);
+        Ok(())
+    }
+
+    /// Rename the font at `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FontError::TdfNameTooLong`] if `name` is longer than [`MAX_TDF_NAME_LEN`]
+    /// bytes.
+    pub fn rename(&mut self, index: usize, name: impl Into<String>) -> Result<()> {
+        self.check(index)?;
+        let name = name.into();
+        if name.len() > MAX_TDF_NAME_LEN {
+            return Err(FontError::TdfNameTooLong {
+                len: name.len(),
+                max: MAX_TDF_NAME_LEN,
+            });
+        }
+        self.fonts[index].name = name;
+        Ok(())
+    }
+
+    /// Append the fonts of `other`. The trailer of `self` is kept.
+    pub fn merge(&mut self, other: TdfBundle) {
+        self.fonts.extend(other.fonts);
+    }
+
+    /// One bundle per font, each with a copy of the trailer so SAUCE credits stay attached.
+    pub fn split(&self) -> Vec<TdfBundle> {
+        self.fonts
+            .iter()
+            .map(|f| TdfBundle {
+                fonts: vec![f.clone()],
+                trailer: self.trailer.clone(),
+            })
+            .collect()
+    }
+
+    fn check(&self, index: usize) -> Result<()> {
+        if index < self.fonts.len() {
+            Ok(())
+        } else {
+            Err(self.out_of_range(index))
+        }
+    }
+
+    fn out_of_range(&self, index: usize) -> FontError {
+        FontError::TdfFontIndexOutOfRange {
+            index,
+            len: self.fonts.len(),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Horizontal text layout onto a 2D cell grid.
+use crate::{stack::FontStack, Cell, Font, FontTarget, RenderOptions, Result, VerticalAlign};
+
+/// A rendering buffer that accumulates glyphs horizontally.
+///
+/// When rendering text like "Hello", each character's glyph is multi-line (e.g., ASCII art).
+/// The canvas places glyphs side-by-side by maintaining an X position that advances after
+/// each character, while `next_line()` moves to the next row within the current glyph
+/// without changing X.
+///
+/// Positions that were never drawn, or that a glyph skipped (transparent cells), are `None`.
+#[derive(Clone, Debug)]
+pub struct Canvas {
+    lines: Vec<Vec<Option<Cell>>>,
+    cur_line: usize,
+    cur_x: usize,
+    // First column of each character, in text order
+    glyph_starts: Vec<usize>,
+}
+
+impl Canvas {
+    pub fn new() -> Self {
+        Self {
+            lines: vec![Vec::new()],
+            cur_line: 0,
+            cur_x: 0,
+            glyph_starts: vec![0],
+        }
+    }
+
+    /// Render `text` glyph by glyph, placing the glyphs side-by-side and aligning them as
+    /// set in [`RenderOptions::align`].
+    pub fn render_text(font: &Font, text: &str, options: &RenderOptions) -> Result<Self> {
+        let glyph_top = |ch: char| {
+            let (_, height) = font.glyph_size(font.resolve_char(ch))?;
+            Some(font.baseline() as isize + font.descent(ch) as isize - height as isize)
+        };
+        Self::layout(text, options, glyph_top, |canvas, ch| {
+            font.render_glyph(canvas, ch, options)
+        })
+    }
+
+    /// Render `text` like [`Canvas::render_text`], taking each glyph from the first font of
+    /// the stack that has it.
+    pub fn render_stack(stack: &FontStack, text: &str, options: &RenderOptions) -> Result<Self> {
+        let glyph_top = |ch: char| {
+            let height = stack.glyph(ch)?.height;
+            Some(stack.baseline() as isize + stack.descent(ch) as isize - height as isize)
+        };
+        Self::layout(text, options, glyph_top, |canvas, ch| {
+            stack.render_glyph(canvas, ch, options)
+        })
+    }
+
+    /// Place the glyphs side-by-side. For [`VerticalAlign::Baseline`], `glyph_top` gives the
+    /// row a glyph starts on when it sits on the baseline; it may be negative.
+    fn layout(
+        text: &str,
+        options: &RenderOptions,
+        glyph_top: impl Fn(char) -> Option<isize>,
+        mut render: impl FnMut(&mut Canvas, char) -> Result<()>,
+    ) -> Result<Self> {
+        let tops = match options.align {
+            VerticalAlign::Top => vec![0; text.chars().count()],
+            VerticalAlign::Baseline => {
+                let tops: Vec<Option<isize>> = text.chars().map(glyph_top).collect();
+                // Everything shifts down by `-min` so the highest glyph starts on row 0.
+                // Glyph-less spaces start where a glyph of the full font height would, which
+                // is row `-min`, not the first row.
+                let min = tops.iter().flatten().copied().min().unwrap_or(0).min(0);
+                tops.iter()
+                    .map(|top| (top.unwrap_or(0) - min) as usize)
+                    .collect()
+            }
+        };
+        let mut canvas = Canvas::new();
+        for (ch, top) in text.chars().zip(tops) {
+            canvas.cur_line = top;
+            render(&mut canvas, ch)?;
+            canvas.next_char();
+        }
+        Ok(canvas)
+    }
+
+    /// Reset to the next character position (advances X, resets Y to 0).
+    pub fn next_char(&mut self) {
+        self.cur_x = self.width();
+        self.cur_line = 0;
+        self.glyph_starts.push(self.cur_x);
+    }
+
+    /// Index of the character whose glyph covers column `x`, counted from 0 in text order.
+    pub fn glyph_index(&self, x: usize) -> usize {
+        self.glyph_starts
+            .partition_point(|&start| start <= x)
+            .saturating_sub(1)
+    }
+
+    /// Width of the widest line.
+    pub fn width(&self) -> usize {
+        self.lines.iter().map(|line| line.len()).max().unwrap_or(0)
+    }
+
+    /// Number of lines.
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Rendered lines; `None` marks a transparent position.
+    pub fn lines(&self) -> &[Vec<Option<Cell>>] {
+        &self.lines
+    }
+
+    /// Returns the cell at (x, y), or `None` if the position is transparent or out of range.
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell> {
+        self.lines.get(y)?.get(x)?.as_ref()
+    }
+
+    /// Mutable access to the cell at (x, y), or `None` if the position is transparent or out
+    /// of range.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
+        self.lines.get_mut(y)?.get_mut(x)?.as_mut()
+    }
+
+    /// Replace the cell at (x, y), growing the canvas with transparent positions if needed.
+    /// `None` makes the position transparent.
+    pub fn set(&mut self, x: usize, y: usize, cell: Option<Cell>) {
+        if self.lines.len() <= y {
+            self.lines.resize(y + 1, Vec::new());
+        }
+        let line = &mut self.lines[y];
+        if line.len() <= x {
+            line.resize(x + 1, None);
+        }
+        line[x] = cell;
+    }
+
+    /// Move the content right by `dx` and down by `dy`, filling the gap with transparent
+    /// positions.
+    pub fn shift(&mut self, dx: usize, dy: usize) {
+        for line in &mut self.lines {
+            line.splice(0..0, std::iter::repeat_n(None, dx));
+        }
+        self.lines.splice(0..0, std::iter::repeat_n(Vec::new(), dy));
+        self.cur_x += dx;
+        for start in &mut self.glyph_starts {
+            *start += dx;
+        }
+    }
+}
+
+impl Default for Canvas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FontTarget for Canvas {
+    type Error = std::fmt::Error;
+
+    fn draw(&mut self, cell: Cell) -> std::result::Result<(), Self::Error> {
+        while self.cur_line >= self.lines.len() {
+            self.lines.push(Vec::new());
+        }
+        // Extend the current line to cur_x if needed (transparent padding)
+        let line = &mut self.lines[self.cur_line];
+        if line.len() < self.cur_x {
+            line.resize(self.cur_x, None);
+        }
+        line.push(Some(cell));
+        Ok(())
+    }
+
+    fn next_line(&mut self) -> std::result::Result<(), Self::Error> {
+        // Move to the next line within the current glyph (preserves X position)
+        self.cur_line += 1;
+        Ok(())
+    }
+
+    fn skip(&mut self) -> std::result::Result<(), Self::Error> {
+        while self.cur_line >= self.lines.len() {
+            self.lines.push(Vec::new());
+        }
+        let line = &mut self.lines[self.cur_line];
+        if line.len() < self.cur_x {
+            line.resize(self.cur_x, None);
+        }
+        line.push(None);
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+//! Colorizing monochrome renderings.
+//!
+//! FIGlet and Block TDF fonts produce cells without colors. A [`Colorizer`] paints those cells
+//! after layout with a solid color, a gradient or a rainbow, similar to TOIlet's filters.
+//! Cells that already carry a color (Color TDF fonts) are left alone. [`CellColors`] applies
+//! fixed foreground/background colors and can optionally override Color fonts as well.
+use crate::{canvas::Canvas, palette::Palette, Color, Rgb};
+
+/// Direction in which a gradient runs across the canvas.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GradientDirection {
+    /// Left to right.
+    #[default]
+    Horizontal,
+    /// Top to bottom.
+    Vertical,
+    /// Top left to bottom right.
+    Diagonal,
+}
+
+impl GradientDirection {
+    /// Parse a direction name as used on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "horizontal" | "h" => Some(GradientDirection::Horizontal),
+            "vertical" | "v" => Some(GradientDirection::Vertical),
+            "diagonal" | "d" => Some(GradientDirection::Diagonal),
+            _ => None,
+        }
+    }
+}
+
+/// How colors are spread over the cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorFill {
+    /// One color for every cell.
+    Solid(Rgb),
+    /// Colors interpolated between evenly spaced stops.
+    Gradient {
+        direction: GradientDirection,
+        stops: Vec<Rgb>,
+    },
+    /// One hue per character, cycling through the spectrum from character to character.
+    Rainbow,
+}
+
+impl ColorFill {
+    /// Names accepted by [`ColorFill::preset`].
+    pub const PRESET_NAMES: [&'static str; 4] = ["fire", "ice", "metal", "rainbow"];
+
+    /// Look up a preset filter by name.
+    pub fn preset(name: &str) -> Option<Self> {
+        let vertical = |stops: &[u32]| ColorFill::Gradient {
+            direction: GradientDirection::Vertical,
+            stops: stops
+                .iter()
+                .map(|v| Rgb::new((v >> 16) as u8, (v >> 8) as u8, *v as u8))
+                .collect(),
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "fire" => Some(vertical(&[0xFFFF55, 0xFFAA00, 0xFF5500, 0xAA0000])),
+            "ice" => Some(vertical(&[0xFFFFFF, 0x55FFFF, 0x5555FF, 0x0000AA])),
+            "metal" => Some(vertical(&[0x5555FF, 0xFFFFFF, 0xAAAAAA, 0x555555])),
+            "rainbow" => Some(ColorFill::Rainbow),
+            _ => None,
+        }
+    }
+
+    /// Color of cell (x, y) on a canvas of the given size; `glyph` is the index of the
+    /// character the cell belongs to (see [`Canvas::glyph_index`]).
+    pub fn color_at(&self, x: usize, y: usize, glyph: usize, width: usize, height: usize) -> Rgb {
+        match self {
+            ColorFill::Solid(c) => *c,
+            ColorFill::Gradient { direction, stops } => {
+                let (pos, extent) = match direction {
+                    GradientDirection::Horizontal => (x, width),
+                    GradientDirection::Vertical => (y, height),
+                    GradientDirection::Diagonal => (x + y, width + height - 1),
+                };
+                let t = if extent > 1 {
+                    pos as f32 / (extent - 1) as f32
+                } else {
+                    0.0
+                };
+                interpolate(stops, t)
+            }
+            ColorFill::Rainbow => hue(glyph as f32 * 30.0),
+        }
+    }
+}
+
+fn interpolate(stops: &[Rgb], t: f32) -> Rgb {
+    match stops.len() {
+        0 => Rgb::default(),
+        1 => stops[0],
+        n => {
+            let pos = t.clamp(0.0, 1.0) * (n - 1) as f32;
+            let i = (pos as usize).min(n - 2);
+            let f = pos - i as f32;
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+            let (a, b) = (stops[i], stops[i + 1]);
+            Rgb::new(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b))
+        }
+    }
+}
+
+/// Fully saturated color for a hue in degrees.
+fn hue(degrees: f32) -> Rgb {
+    let h = degrees.rem_euclid(360.0) / 60.0;
+    let x = ((1.0 - (h % 2.0 - 1.0).abs()) * 255.0).round() as u8;
+    match h as u32 {
+        0 => Rgb::new(255, x, 0),
+        1 => Rgb::new(x, 255, 0),
+        2 => Rgb::new(0, 255, x),
+        3 => Rgb::new(0, x, 255),
+        4 => Rgb::new(x, 0, 255),
+        _ => Rgb::new(255, 0, x),
+    }
+}
+
+/// Paints uncolored canvas cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Colorizer {
+    pub fill: ColorFill,
+    /// Keep exact RGB values for true color output. Otherwise cells only get the nearest
+    /// palette index.
+    pub true_color: bool,
+    /// Palette used to find the nearest color index.
+    pub palette: Palette,
+}
+
+impl Colorizer {
+    pub fn new(fill: ColorFill) -> Self {
+        Self {
+            fill,
+            true_color: true,
+            palette: Palette::default(),
+        }
+    }
+
+    /// Color every cell of `canvas` that has no foreground color yet.
+    pub fn apply(&self, canvas: &mut Canvas) {
+        let (width, height) = (canvas.width(), canvas.height());
+        for y in 0..height {
+            for x in 0..width {
+                let glyph = canvas.glyph_index(x);
+                let Some(cell) = canvas.get_mut(x, y) else {
+                    continue;
+                };
+                if cell.fg.is_some() {
+                    continue;
+                }
+                let color = self.fill.color_at(x, y, glyph, width, height);
+                cell.fg = Some(self.palette.nearest(color));
+                cell.fg_rgb = self.true_color.then_some(color);
+            }
+        }
+    }
+}
+
+/// Fixed foreground and background colors for fonts without their own colors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellColors {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    /// Also recolor cells that already have colors (Color TDF fonts).
+    pub override_colored: bool,
+    /// Backgrounds may use the bright palette colors (8-15). Without iCE colors RGB
+    /// backgrounds are matched against 0-7 only and index backgrounds 8-15 are clamped to
+    /// their dark counterparts (0-7).
+    pub ice_colors: bool,
+}
+
+impl CellColors {
+    /// Apply the colors to the canvas; `palette` supplies the nearest index for RGB values.
+    pub fn apply(&self, canvas: &mut Canvas, palette: &Palette) {
+        let (width, height) = (canvas.width(), canvas.height());
+        for y in 0..height {
+            for x in 0..width {
+                let Some(cell) = canvas.get_mut(x, y) else {
+                    continue;
+                };
+                if !self.override_colored && (cell.fg.is_some() || cell.bg.is_some()) {
+                    continue;
+                }
+                if let Some(fg) = self.fg {
+                    (cell.fg, cell.fg_rgb) = match fg {
+                        Color::Index(i) => (Some(i), None),
+                        Color::Rgb(c) => (Some(palette.nearest(c)), Some(c)),
+                    };
+                }
+                if let Some(bg) = self.bg {
+                    (cell.bg, cell.bg_rgb) = match bg {
+                        Color::Index(i) if !self.ice_colors => (Some(i & 0x07), None),
+                        Color::Index(i) => (Some(i), None),
+                        Color::Rgb(c) => {
+                            let count = if self.ice_colors { 16 } else { 8 };
+                            (Some(palette.nearest_of(c, count)), Some(c))
+                        }
+                    };
+                }
+            }
+        }
+    }
+}
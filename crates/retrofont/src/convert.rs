@@ -1,9 +1,21 @@
-//! Conversion stubs (FIGlet -> TDF)
+//! Conversion between FIGlet, TOIlet and TDF fonts.
+//!
+//! [`convert`] converts between any two [`FontKind`]s and reports what was lost on the way.
+//! The specialized functions ([`figlet_to_tdf`], [`tdf_to_figlet`], [`bake_outline`], ...)
+//! cover the single directions.
+use std::fmt;
+
 use crate::{
+    cp437::{Cp437Options, UnmappablePolicy},
     error::{FontError, Result},
-    figlet::FigletFont,
-    glyph::{Glyph, GlyphPart},
-    tdf::{TdfFont, TdfFontType, MAX_TDF_GLYPH_HEIGHT, MAX_TDF_GLYPH_WIDTH},
+    figlet::{FigletFont, FigletFormat, FigletLayout},
+    glyph::{transform_outline, Glyph, GlyphPart},
+    tdf::{
+        TdfFont, TdfFontType, CP437_TO_UNICODE, MAX_TDF_GLYPH_HEIGHT, MAX_TDF_GLYPH_WIDTH,
+        MAX_TDF_NAME_LEN,
+    },
+    transform::QUARTER_BLOCKS,
+    Font,
 };
 
 /// TDF supports printable ASCII range: '!' (0x21) through '~' (0x7E) = 94 characters
@@ -29,22 +41,25 @@ pub fn can_convert_figlet_to_tdf(fig: &FigletFont, _target_type: TdfFontType) ->
 /// Convert a FIGlet font into a TDF font with the requested target type.
 ///
 /// Only converts characters in the TDF printable range (! through ~).
-/// Characters outside this range are skipped.
+/// Characters outside this range are skipped, and so are glyphs over the TDF size limits;
+/// run the font through [`fit_to_tdf`] first to keep them.
 ///
 /// Notes:
-/// * Spacing is heuristically set to 1; future versions may derive optimal spacing.
-/// * Color/outline conversion is currently a straight part copy; smarter outline mapping could
-///   collapse placeholder sets.
+/// * Blanks left and right of the ink in each row become transparent [`GlyphPart::Skip`]
+///   cells, blanks inside the ink stay visible spaces and hard blanks stay hard blanks.
+/// * Trailing blank columns are trimmed. The spacing is derived from them for full width
+///   fonts and is 0 for kerning and smushing fonts, whose glyphs touch or overlap.
+/// * Color conversion is a straight part copy with the DOS default colors.
+/// * Outline conversion traces the glyph strokes, see [`figlet_to_outline_tdf`]. Use that
+///   function directly to learn which glyphs fell back to full blocks.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The target type is not supported (must be Block, Color)
-/// - Outline is currently unsupported
 /// - The font is incompatible with the target type
 pub fn figlet_to_tdf(fig: &FigletFont, target_type: TdfFontType) -> Result<TdfFont> {
-    if !matches!(target_type, TdfFontType::Color | TdfFontType::Block) {
-        return Err(FontError::UnsupportedType);
+    if target_type == TdfFontType::Outline {
+        return figlet_to_outline_tdf(fig).map(|conversion| conversion.font);
     }
 
     // Check compatibility
@@ -52,94 +67,1178 @@ pub fn figlet_to_tdf(fig: &FigletFont, target_type: TdfFontType) -> Result<TdfFo
         return Err(FontError::ConversionIncompatible);
     }
 
-    let mut tdf = TdfFont::new(fig.name.clone(), target_type, 1);
+    let glyphs: Vec<(char, Glyph, Option<usize>)> = tdf_fitting_glyphs(fig)
+        .map(|(ch, g)| {
+            let (glyph, gap) = transparent_glyph(g);
+            (ch, glyph, gap)
+        })
+        .collect();
+    let spacing = derive_spacing(fig, glyphs.iter().filter_map(|(_, _, gap)| *gap));
+    let mut tdf = TdfFont::new(fig.name.clone(), target_type, spacing);
+    for (ch, mut glyph, _) in glyphs {
+        if target_type == TdfFontType::Color {
+            for part in &mut glyph.parts {
+                if let GlyphPart::Char(c) = part {
+                    *part = GlyphPart::AnsiChar {
+                        ch: *c,
+                        fg: 7, // Light gray (DOS default foreground)
+                        bg: 0, // Black (DOS default background)
+                        blink: false,
+                    };
+                }
+            }
+        } else if let Some(plain) = strip_colors(&glyph) {
+            glyph = plain;
+        }
+        tdf.add_glyph(ch, glyph);
+    }
+
+    Ok(tdf)
+}
+
+/// Result of [`figlet_to_outline_tdf`].
+#[derive(Clone)]
+pub struct OutlineConversion {
+    pub font: TdfFont,
+    /// Glyphs that could not be traced cleanly and were drawn with `█` instead.
+    pub block_fallback: Vec<char>,
+}
+
+/// Convert a FIGlet font into an Outline TDF font by tracing the glyph strokes.
+///
+/// Every non-space character of a FIGlet glyph is treated as ink. Ink cells on the edge of a
+/// stroke become outline placeholders: `A`/`B` on top/bottom edges, `C`/`D` on left/right
+/// edges, `E`/`F`/`K`/`L` on convex corners and `G`/`H`/`I`/`J` on concave corners. Inner
+/// ink cells become `@` fills and enclosed background becomes an `O` hole, so the font can
+/// be rendered with all outline styles.
+///
+/// Strokes that are only one cell thick have no room for an outline. Glyphs containing them
+/// are drawn with `█` instead and listed in [`OutlineConversion::block_fallback`]; their own
+/// characters could be read back as placeholders, fills or end markers. Glyphs that exceed
+/// the TDF size limits are skipped.
+///
+/// # Errors
+///
+/// Returns [`FontError::ConversionIncompatible`] if the font has no glyphs in the TDF range.
+pub fn figlet_to_outline_tdf(fig: &FigletFont) -> Result<OutlineConversion> {
+    if !can_convert_figlet_to_tdf(fig, TdfFontType::Outline) {
+        return Err(FontError::ConversionIncompatible);
+    }
+
+    let glyphs: Vec<(char, Glyph, Option<usize>)> = tdf_fitting_glyphs(fig)
+        .map(|(ch, g)| {
+            let (glyph, gap) = transparent_glyph(g);
+            (ch, glyph, gap)
+        })
+        .collect();
+    let spacing = derive_spacing(fig, glyphs.iter().filter_map(|(_, _, gap)| *gap));
+    Ok(trace_glyphs(
+        TdfFont::new(fig.name.clone(), TdfFontType::Outline, spacing),
+        glyphs.iter().map(|(ch, g, _)| (*ch, g)),
+    ))
+}
+
+/// Glyphs in the TDF range that fit the TDF size limits.
+fn tdf_fitting_glyphs(fig: &FigletFont) -> impl Iterator<Item = (char, &Glyph)> {
+    (TDF_FIRST_CHAR..=TDF_LAST_CHAR)
+        .filter_map(|ch| fig.glyph(ch).map(|g| (ch, g)))
+        .filter(|(_, g)| g.width <= MAX_TDF_GLYPH_WIDTH && g.height <= MAX_TDF_GLYPH_HEIGHT)
+}
+
+/// Copy of a FIGlet glyph with the blanks around the ink of each row turned into
+/// [`GlyphPart::Skip`] and trailing blank columns removed, plus the number of removed
+/// columns (`None` for glyphs without ink).
+fn transparent_glyph(glyph: &Glyph) -> (Glyph, Option<usize>) {
+    let mut rows = part_rows(glyph);
+    let mut width = 0;
+    for row in &mut rows {
+        let ink = |p: &GlyphPart| !p.is_blank();
+        let Some(first) = row.iter().position(ink) else {
+            row.fill(GlyphPart::Skip);
+            continue;
+        };
+        let last = row.iter().rposition(ink).unwrap_or(first);
+        row[..first].fill(GlyphPart::Skip);
+        row.truncate(last + 1);
+        width = width.max(last + 1);
+    }
+    let gap = (width > 0).then(|| glyph.width.saturating_sub(width));
+    let width = if width == 0 { glyph.width } else { width };
+    for row in &mut rows {
+        row.resize(width, GlyphPart::Skip);
+    }
+    (rows_to_glyph(rows, width), gap)
+}
+
+/// TDF spacing for a FIGlet font: the most common trailing gap for full width fonts, 0 for
+/// kerning and smushing fonts.
+fn derive_spacing(fig: &FigletFont, gaps: impl Iterator<Item = usize>) -> i32 {
+    if fig.layout() != FigletLayout::FullWidth {
+        return 0;
+    }
+    let mut counts = [0usize; MAX_TDF_GLYPH_WIDTH + 1];
+    for gap in gaps {
+        counts[gap.min(MAX_TDF_GLYPH_WIDTH)] += 1;
+    }
+    // Ties go to the smaller gap
+    let (gap, count) = counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(gap, count)| (**count, std::cmp::Reverse(*gap)))
+        .unwrap();
+    if *count == 0 {
+        1
+    } else {
+        gap as i32
+    }
+}
+
+/// Trace glyphs into the outline font `font`, see [`figlet_to_outline_tdf`].
+fn trace_glyphs<'a>(
+    font: TdfFont,
+    glyphs: impl Iterator<Item = (char, &'a Glyph)>,
+) -> OutlineConversion {
+    let mut conversion = OutlineConversion {
+        font,
+        block_fallback: Vec::new(),
+    };
+    for (code, g) in glyphs {
+        if g.width > MAX_TDF_GLYPH_WIDTH || g.height > MAX_TDF_GLYPH_HEIGHT {
+            continue;
+        }
+        let glyph = trace_outline(g).unwrap_or_else(|| {
+            conversion.block_fallback.push(code);
+            block_copy(g)
+        });
+        conversion.font.add_glyph(code, glyph);
+    }
+    conversion
+}
 
-    // Only convert characters in the TDF printable range: ! (0x21) through ~ (0x7E)
-    for code in TDF_FIRST_CHAR..=TDF_LAST_CHAR {
-        if let Some(g) = fig.glyph(code) {
-            // Skip glyphs that exceed TDF dimension limits
-            if g.width > MAX_TDF_GLYPH_WIDTH || g.height > MAX_TDF_GLYPH_HEIGHT {
-                continue;
+/// Characters of a FIGlet glyph as a grid, hard blanks as spaces.
+fn char_grid(glyph: &Glyph) -> Vec<Vec<char>> {
+    let mut grid = vec![Vec::new()];
+    for part in &glyph.parts {
+        match part {
+            GlyphPart::NewLine => grid.push(Vec::new()),
+            GlyphPart::Char(c) | GlyphPart::AnsiChar { ch: c, .. } => {
+                grid.last_mut().unwrap().push(*c)
             }
+            _ => grid.last_mut().unwrap().push(' '),
+        }
+    }
+    grid
+}
 
-            let mut parts = Vec::new();
-            let mut width = 0usize;
-            let mut line_width = 0usize;
-            let mut lines = 1usize;
+fn ink_at(grid: &[Vec<char>], x: isize, y: isize) -> bool {
+    x >= 0
+        && y >= 0
+        && grid
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .is_some_and(|c| *c != ' ')
+}
 
-            for part in &g.parts {
-                match part {
-                    GlyphPart::NewLine => {
-                        parts.push(GlyphPart::NewLine);
-                        width = width.max(line_width);
-                        line_width = 0;
-                        lines += 1;
-                    }
-                    // Single-char parts (Char, HardBlank, EndMarker)
-                    GlyphPart::Char(c) => {
-                        // Convert to Colored if target is Color type, otherwise keep as Char
-                        if target_type == TdfFontType::Color {
-                            parts.push(GlyphPart::AnsiChar {
-                                ch: *c,
-                                fg: 7, // Light gray (DOS default foreground)
-                                bg: 0, // Black (DOS default background)
-                                blink: false,
-                            });
-                        } else {
-                            parts.push(GlyphPart::Char(*c));
+/// Outline part for an ink cell, or `None` if the cell has no room for an outline.
+fn outline_part(grid: &[Vec<char>], x: isize, y: isize) -> Option<GlyphPart> {
+    let bg = |dx, dy| !ink_at(grid, x + dx, y + dy);
+    let (n, s, w, e) = (bg(0, -1), bg(0, 1), bg(-1, 0), bg(1, 0));
+    let (nw, ne, sw, se) = (bg(-1, -1), bg(1, -1), bg(-1, 1), bg(1, 1));
+    let placeholder = match (n, s, w, e) {
+        (false, false, false, false) => match (nw, ne, sw, se) {
+            (false, false, false, false) => return Some(GlyphPart::FillMarker),
+            (true, false, false, false) => b'J',
+            (false, true, false, false) => b'I',
+            (false, false, true, false) => b'H',
+            (false, false, false, true) => b'G',
+            _ => return None,
+        },
+        (true, false, false, false) if !sw && !se => b'A',
+        (false, true, false, false) if !nw && !ne => b'B',
+        (false, false, true, false) if !ne && !se => b'C',
+        (false, false, false, true) if !nw && !sw => b'D',
+        (true, false, true, false) if !se => b'E',
+        (true, false, false, true) if !sw => b'F',
+        (false, true, true, false) if !ne => b'K',
+        (false, true, false, true) if !nw => b'L',
+        _ => return None,
+    };
+    Some(GlyphPart::OutlinePlaceholder(placeholder))
+}
+
+fn trace_outline(glyph: &Glyph) -> Option<Glyph> {
+    let grid = char_grid(glyph);
+    let (width, height) = (glyph.width, grid.len());
+
+    // Background reachable from the glyph border; the rest is enclosed.
+    let mut outside = vec![vec![false; width]; height];
+    let mut stack: Vec<(isize, isize)> = (0..height as isize)
+        .flat_map(|y| [(0, y), (width as isize - 1, y)])
+        .chain((0..width as isize).flat_map(|x| [(x, 0), (x, height as isize - 1)]))
+        .collect();
+    while let Some((x, y)) = stack.pop() {
+        if x < 0 || y < 0 || x as usize >= width || y as usize >= height || ink_at(&grid, x, y) {
+            continue;
+        }
+        if std::mem::replace(&mut outside[y as usize][x as usize], true) {
+            continue;
+        }
+        stack.extend([(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]);
+    }
+
+    let mut parts = Vec::new();
+    for (y, outside_row) in outside.iter().enumerate() {
+        if y > 0 {
+            parts.push(GlyphPart::NewLine);
+        }
+        for (x, outside) in outside_row.iter().enumerate() {
+            parts.push(if ink_at(&grid, x as isize, y as isize) {
+                outline_part(&grid, x as isize, y as isize)?
+            } else if *outside {
+                GlyphPart::Skip
+            } else {
+                GlyphPart::OutlineHole
+            });
+        }
+    }
+    Some(Glyph {
+        width,
+        height,
+        parts,
+    })
+}
+
+/// Draw the ink of a FIGlet glyph as `█`, with spaces as transparent cells.
+///
+/// The original characters are not kept: in an Outline font `A`-`R`, `@`, `O` and `&` would
+/// be read back as placeholders, fills, holes and end markers.
+fn block_copy(glyph: &Glyph) -> Glyph {
+    let grid = char_grid(glyph);
+    let mut parts = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        if y > 0 {
+            parts.push(GlyphPart::NewLine);
+        }
+        parts.extend(row.iter().map(|c| match c {
+            ' ' => GlyphPart::Skip,
+            _ => GlyphPart::Char('█'),
+        }));
+    }
+    Glyph {
+        width: glyph.width,
+        height: grid.len(),
+        parts,
+    }
+}
+
+/// How [`fit_to_tdf`] handles FIGlet fonts with glyphs over the TDF size limits
+/// ([`MAX_TDF_GLYPH_WIDTH`] x [`MAX_TDF_GLYPH_HEIGHT`]).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GlyphFit {
+    /// Leave oversized glyphs out.
+    #[default]
+    Drop,
+    /// Remove blank rows and columns, then cut off whatever still doesn't fit.
+    Crop,
+    /// Remove blank rows and columns, then halve the font with half-block characters
+    /// (quarter blocks when both directions are halved) until it fits.
+    Downscale,
+    /// Remove blank rows and columns, then split tall fonts into bands of
+    /// [`MAX_TDF_GLYPH_HEIGHT`] rows, one font per band. Glyphs that are still too wide are
+    /// left out.
+    Split,
+}
+
+impl GlyphFit {
+    /// Parse a mode name as used on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "drop" => Some(GlyphFit::Drop),
+            "crop" => Some(GlyphFit::Crop),
+            "downscale" | "scale" => Some(GlyphFit::Downscale),
+            "split" => Some(GlyphFit::Split),
+            _ => None,
+        }
+    }
+}
+
+/// Result of [`fit_to_tdf`].
+#[derive(Clone)]
+pub struct FittedFont {
+    /// The fitted font, or one font per band of rows for [`GlyphFit::Split`].
+    pub fonts: Vec<FigletFont>,
+    /// Oversized glyphs that were cut down or scaled.
+    pub clipped: Vec<ClippedGlyph>,
+}
+
+/// Prepare a FIGlet font for TDF conversion by fitting oversized glyphs into the TDF limits.
+///
+/// Fonts without oversized glyphs in the TDF range are returned unchanged. Otherwise the
+/// blank rows all glyphs share at the top and bottom are removed and oversized glyphs lose
+/// their blank leading and trailing columns before `fit` applies. Scaling and splitting work
+/// on the whole font so all glyphs keep the same proportions.
+///
+/// Split fonts are named `"<name> 1"`, `"<name> 2"`, ..., with the name shortened to fit the
+/// TDF name field.
+pub fn fit_to_tdf(fig: &FigletFont, fit: GlyphFit) -> FittedFont {
+    let oversized = |g: &Glyph| g.width > MAX_TDF_GLYPH_WIDTH || g.height > MAX_TDF_GLYPH_HEIGHT;
+    let tdf_glyphs =
+        || (TDF_FIRST_CHAR..=TDF_LAST_CHAR).filter_map(|ch| fig.glyph(ch).map(|g| (ch, g)));
+    if fit == GlyphFit::Drop || !tdf_glyphs().any(|(_, g)| oversized(g)) {
+        return FittedFont {
+            fonts: vec![fig.clone()],
+            clipped: Vec::new(),
+        };
+    }
+
+    let mut glyphs: Vec<(char, Vec<Vec<GlyphPart>>)> =
+        tdf_glyphs().map(|(ch, g)| (ch, part_rows(g))).collect();
+    let height = glyphs.iter().map(|(_, r)| r.len()).max().unwrap_or(0);
+    for (_, rows) in &mut glyphs {
+        rows.resize(height, Vec::new());
+    }
+    let blank_row = |y: usize| {
+        glyphs
+            .iter()
+            .all(|(_, r)| r[y].iter().all(GlyphPart::is_blank))
+    };
+    let top = (0..height).take_while(|y| blank_row(*y)).count();
+    let bottom = (top..height).rev().take_while(|y| blank_row(*y)).count();
+    for (ch, rows) in &mut glyphs {
+        rows.truncate(height - bottom);
+        rows.drain(..top);
+        if fig
+            .glyph(*ch)
+            .is_some_and(|g| g.width > MAX_TDF_GLYPH_WIDTH)
+        {
+            trim_columns(rows);
+        }
+    }
+
+    let max_width = |glyphs: &[(char, Vec<Vec<GlyphPart>>)]| {
+        glyphs
+            .iter()
+            .flat_map(|(_, r)| r.iter().map(Vec::len))
+            .max()
+            .unwrap_or(0)
+    };
+    match fit {
+        GlyphFit::Drop => unreachable!(),
+        GlyphFit::Crop => {
+            for (_, rows) in &mut glyphs {
+                rows.truncate(MAX_TDF_GLYPH_HEIGHT);
+                for row in rows.iter_mut() {
+                    row.truncate(MAX_TDF_GLYPH_WIDTH);
+                }
+            }
+        }
+        GlyphFit::Downscale => loop {
+            let sx = if max_width(&glyphs) > MAX_TDF_GLYPH_WIDTH {
+                2
+            } else {
+                1
+            };
+            let sy = if glyphs[0].1.len() > MAX_TDF_GLYPH_HEIGHT {
+                2
+            } else {
+                1
+            };
+            if sx == 1 && sy == 1 {
+                break;
+            }
+            for (_, rows) in &mut glyphs {
+                *rows = downscale(rows, sx, sy);
+            }
+        },
+        GlyphFit::Split => {}
+    }
+
+    let rows_height = glyphs.first().map_or(0, |(_, r)| r.len());
+    let bands = if fit == GlyphFit::Split {
+        rows_height.div_ceil(MAX_TDF_GLYPH_HEIGHT).max(1)
+    } else {
+        1
+    };
+    let mut fonts = Vec::with_capacity(bands);
+    for band in 0..bands {
+        let mut font = fig.clone();
+        if bands > 1 {
+            font.name = tdf_part_name(&fig.name, band + 1);
+        }
+        let rows =
+            band * MAX_TDF_GLYPH_HEIGHT..((band + 1) * MAX_TDF_GLYPH_HEIGHT).min(rows_height);
+        for (ch, glyph_rows) in &glyphs {
+            let band_rows = if bands > 1 {
+                glyph_rows[rows.clone()].to_vec()
+            } else {
+                glyph_rows.clone()
+            };
+            let width = band_rows.iter().map(Vec::len).max().unwrap_or(0);
+            font.add_glyph(*ch, rows_to_glyph(band_rows, width));
+        }
+        fonts.push(font);
+    }
+
+    // Splitting keeps every row
+    let clipped = tdf_glyphs()
+        .filter(|(_, g)| fit != GlyphFit::Split && oversized(g))
+        .filter_map(|(ch, g)| {
+            let to = fonts[0].glyph(ch)?;
+            Some(ClippedGlyph {
+                ch,
+                from: (g.width, g.height),
+                to: (to.width, rows_height.min(MAX_TDF_GLYPH_HEIGHT)),
+            })
+        })
+        .filter(|c| c.to.0 <= MAX_TDF_GLYPH_WIDTH)
+        .collect();
+    FittedFont { fonts, clipped }
+}
+
+/// Split a glyph into rows of parts.
+fn part_rows(glyph: &Glyph) -> Vec<Vec<GlyphPart>> {
+    let mut rows = vec![Vec::new()];
+    for part in &glyph.parts {
+        match part {
+            GlyphPart::NewLine => rows.push(Vec::new()),
+            part => rows.last_mut().unwrap().push(part.clone()),
+        }
+    }
+    rows
+}
+
+/// Remove the blank columns left and right of the ink.
+fn trim_columns(rows: &mut [Vec<GlyphPart>]) {
+    let leading = rows
+        .iter()
+        .map(|r| r.iter().take_while(|p| p.is_blank()).count())
+        .min()
+        .unwrap_or(0);
+    for row in rows.iter_mut() {
+        row.drain(..leading.min(row.len()));
+        while row.last().is_some_and(GlyphPart::is_blank) {
+            row.pop();
+        }
+    }
+}
+
+/// Shrink rows by `sx` (1 or 2) horizontally and `sy` (1 or 2) vertically, drawing each
+/// block of cells as the half or quarter block covering its ink.
+fn downscale(rows: &[Vec<GlyphPart>], sx: usize, sy: usize) -> Vec<Vec<GlyphPart>> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut out = Vec::new();
+    for y in (0..rows.len()).step_by(sy) {
+        let mut row = Vec::new();
+        for x in (0..width).step_by(sx) {
+            let mut mask = 0;
+            let mut colors = None;
+            let mut hard_blank = false;
+            for (dy, dx) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                // Quarter bits: top-left 8, top-right 4, bottom-left 2, bottom-right 1
+                let bit = 8 >> (dy * 2 + dx);
+                let cell = rows
+                    .get(y + dy.min(sy - 1))
+                    .and_then(|r| r.get(x + dx.min(sx - 1)));
+                match cell {
+                    Some(GlyphPart::HardBlank) => hard_blank = true,
+                    Some(part) if !part.is_blank() => {
+                        mask |= bit;
+                        if let (None, GlyphPart::AnsiChar { fg, bg, blink, .. }) = (colors, part) {
+                            colors = Some((*fg, *bg, *blink));
                         }
-                        line_width += 1;
                     }
-                    GlyphPart::HardBlank => {
-                        parts.push(GlyphPart::HardBlank);
-                        line_width += 1;
+                    _ => {}
+                }
+            }
+            let ch = QUARTER_BLOCKS[mask];
+            row.push(match colors {
+                _ if mask == 0 && hard_blank => GlyphPart::HardBlank,
+                Some((fg, bg, blink)) => GlyphPart::AnsiChar { ch, fg, bg, blink },
+                None => GlyphPart::Char(ch),
+            });
+        }
+        out.push(row);
+    }
+    out
+}
+
+/// `"<name> <n>"`, with `name` shortened to fit the TDF name field.
+fn tdf_part_name(name: &str, n: usize) -> String {
+    let suffix = format!(" {n}");
+    let mut end = MAX_TDF_NAME_LEN
+        .saturating_sub(suffix.len())
+        .min(name.len());
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{suffix}", &name[..end])
+}
+
+/// Options for [`bake_outline`].
+#[derive(Clone, Debug)]
+pub struct OutlineBakeOptions {
+    /// Outline style (0-18) used to resolve the outline placeholders.
+    pub outline_style: usize,
+    /// Block or Color.
+    pub target_type: TdfFontType,
+    /// Character drawn for `@` fill cells.
+    pub fill: char,
+    /// Colors of the outline characters (Color target only).
+    pub outline_fg: u8,
+    pub outline_bg: u8,
+    /// Colors of the fill cells (Color target only).
+    pub fill_fg: u8,
+    pub fill_bg: u8,
+}
+
+impl Default for OutlineBakeOptions {
+    fn default() -> Self {
+        Self {
+            outline_style: 0,
+            target_type: TdfFontType::Block,
+            fill: ' ',
+            outline_fg: 7,
+            outline_bg: 0,
+            fill_fg: 7,
+            fill_bg: 0,
+        }
+    }
+}
+
+/// Bake an Outline TDF font into a standalone Block or Color font.
+///
+/// Outline placeholders are resolved with [`transform_outline`], so the baked glyphs look
+/// like the outline font rendered with [`RenderOptions::outline_style`](crate::RenderOptions).
+/// Fill cells become [`OutlineBakeOptions::fill`] and holes become blank cells, both opaque
+/// like in [`Glyph::render`]; skipped cells stay transparent. Since TDF files store spaces as
+/// transparent cells, blank cells are written as hard blanks.
+///
+/// # Errors
+///
+/// Returns [`FontError::UnsupportedType`] if `tdf` is not an Outline font or the target type
+/// is Outline.
+pub fn bake_outline(tdf: &TdfFont, options: &OutlineBakeOptions) -> Result<TdfFont> {
+    if tdf.font_type != TdfFontType::Outline || options.target_type == TdfFontType::Outline {
+        return Err(FontError::UnsupportedType);
+    }
+    let color = options.target_type == TdfFontType::Color;
+    let cell = |ch: char, fg: u8, bg: u8| match (ch, color) {
+        (' ', false) => GlyphPart::HardBlank,
+        (ch, true) => GlyphPart::AnsiChar {
+            // Attributed hard blanks render as spaces
+            ch: if ch == ' ' {
+                CP437_TO_UNICODE[0xFF]
+            } else {
+                ch
+            },
+            fg,
+            bg,
+            blink: false,
+        },
+        (ch, false) => GlyphPart::Char(ch),
+    };
+
+    let mut baked = TdfFont::new(tdf.name.clone(), options.target_type, tdf.spacing);
+    for (ch, glyph) in tdf.iter_glyphs() {
+        let parts = glyph
+            .parts
+            .iter()
+            .filter_map(|part| {
+                Some(match part {
+                    GlyphPart::EndMarker => return None,
+                    GlyphPart::NewLine => GlyphPart::NewLine,
+                    GlyphPart::HardBlank => GlyphPart::HardBlank,
+                    GlyphPart::Skip => GlyphPart::Skip,
+                    // Holes show the background around the glyph
+                    GlyphPart::OutlineHole => cell(' ', options.outline_fg, 0),
+                    GlyphPart::FillMarker => cell(options.fill, options.fill_fg, options.fill_bg),
+                    GlyphPart::OutlinePlaceholder(b) => cell(
+                        transform_outline(options.outline_style, *b),
+                        options.outline_fg,
+                        options.outline_bg,
+                    ),
+                    GlyphPart::Char(c) | GlyphPart::AnsiChar { ch: c, .. } => {
+                        cell(*c, options.outline_fg, options.outline_bg)
                     }
-                    GlyphPart::EndMarker => {
-                        parts.push(GlyphPart::EndMarker);
-                        line_width += 1;
+                })
+            })
+            .collect();
+        baked.add_glyph(
+            ch,
+            Glyph {
+                width: glyph.width,
+                height: glyph.height,
+                parts,
+            },
+        );
+    }
+    Ok(baked)
+}
+
+/// Hard blank candidates for [`tdf_to_figlet`], in order of preference.
+const HARD_BLANK_CANDIDATES: [char; 10] = ['$', '#', '%', '&', '*', '+', '~', '^', '!', '?'];
+
+/// Options for [`tdf_to_figlet`].
+#[derive(Clone, Debug, Default)]
+pub struct FigletExportOptions {
+    /// Outline style (0-18) baked into the glyphs of Outline fonts.
+    pub outline_style: usize,
+    /// Keep the colors of Color fonts. The result is then written as a TOIlet (`.tlf`) font
+    /// with ANSI color escapes; otherwise colors are dropped.
+    pub keep_colors: bool,
+}
+
+/// Convert a TDF font into a FIGlet font.
+///
+/// Glyphs are top-aligned and padded with spaces to the tallest glyph, as TheDraw renders
+/// them. The baseline is the font's `baseline`, or the most common height of the uppercase
+/// letters (see [`TdfFont::detect_baseline`]). Transparent cells and outline fill/hole
+/// markers become spaces, TDF hard blanks become FIGlet hard blanks. The hard blank is a
+/// printable ASCII character that does not appear in any glyph, since FIGlet parsers read it
+/// as a single header byte. The space glyph is `spacing` hard blanks wide.
+///
+/// # Errors
+///
+/// Returns [`FontError::ConversionIncompatible`] if the font has no glyphs, or
+/// [`FontError::NoHardBlank`] if the glyphs use every printable ASCII character.
+pub fn tdf_to_figlet(tdf: &TdfFont, options: &FigletExportOptions) -> Result<FigletFont> {
+    let keep_colors = options.keep_colors && tdf.font_type == TdfFontType::Color;
+    let rows: Vec<(char, Vec<Vec<GlyphPart>>)> = tdf
+        .iter_glyphs()
+        .map(|(ch, g)| (ch, figlet_rows(g, options.outline_style, keep_colors)))
+        .collect();
+    if rows.is_empty() {
+        return Err(FontError::ConversionIncompatible);
+    }
+    let height = rows.iter().map(|(_, r)| r.len()).max().unwrap_or(1).max(1);
+
+    let mut fig = FigletFont::new(tdf.name.clone());
+    fig.comments
+        .push(format!("Converted from TheDraw font \"{}\"", tdf.name));
+    fig.hard_blank = pick_hard_blank(&rows)?;
+    fig.baseline = Some(
+        tdf.baseline
+            .unwrap_or_else(|| tdf.detect_baseline())
+            .min(height),
+    );
+
+    for (ch, mut glyph_rows) in rows {
+        let width = glyph_rows.iter().map(Vec::len).max().unwrap_or(0);
+        glyph_rows.resize(height, Vec::new());
+        fig.add_glyph(ch, rows_to_glyph(glyph_rows, width));
+    }
+    let space_width = tdf.spacing().unwrap_or(1);
+    fig.add_glyph(
+        ' ',
+        rows_to_glyph(
+            vec![vec![GlyphPart::HardBlank; space_width]; height],
+            space_width,
+        ),
+    );
+    Ok(fig)
+}
+
+/// Split a TDF glyph into rows of FIGlet parts (`Char`, `HardBlank` or `AnsiChar`).
+fn figlet_rows(glyph: &Glyph, outline_style: usize, keep_colors: bool) -> Vec<Vec<GlyphPart>> {
+    let mut rows = vec![Vec::new()];
+    for part in &glyph.parts {
+        let row = rows.last_mut().unwrap();
+        match part {
+            GlyphPart::NewLine => rows.push(Vec::new()),
+            GlyphPart::EndMarker => {}
+            GlyphPart::HardBlank => row.push(GlyphPart::HardBlank),
+            GlyphPart::FillMarker | GlyphPart::OutlineHole | GlyphPart::Skip => {
+                row.push(GlyphPart::Char(' '))
+            }
+            GlyphPart::OutlinePlaceholder(b) => {
+                row.push(GlyphPart::Char(transform_outline(outline_style, *b)))
+            }
+            GlyphPart::Char(c) => row.push(GlyphPart::Char(*c)),
+            GlyphPart::AnsiChar { ch, fg, bg, blink } => {
+                // Attributed hard blanks are plain blanks outside TheDraw.
+                let ch = if *ch == CP437_TO_UNICODE[0xFF] {
+                    ' '
+                } else {
+                    *ch
+                };
+                row.push(if keep_colors {
+                    GlyphPart::AnsiChar {
+                        ch,
+                        fg: *fg,
+                        bg: *bg,
+                        blink: *blink,
                     }
-                    GlyphPart::AnsiChar { ch, fg, bg, blink } => {
-                        // If converting to Block or Outline, strip color and use plain Char
-                        if target_type == TdfFontType::Color {
-                            parts.push(GlyphPart::AnsiChar {
-                                ch: *ch,
-                                fg: *fg,
-                                bg: *bg,
-                                blink: *blink,
-                            });
-                        } else {
-                            parts.push(GlyphPart::Char(*ch));
-                        }
-                        line_width += 1;
+                } else {
+                    GlyphPart::Char(ch)
+                });
+            }
+        }
+    }
+    rows
+}
+
+/// Join rows into a glyph, padding every row with spaces to `width`.
+fn rows_to_glyph(rows: Vec<Vec<GlyphPart>>, width: usize) -> Glyph {
+    let height = rows.len();
+    let mut parts = Vec::new();
+    for (i, mut row) in rows.into_iter().enumerate() {
+        if i > 0 {
+            parts.push(GlyphPart::NewLine);
+        }
+        row.resize(width, GlyphPart::Char(' '));
+        parts.extend(row);
+    }
+    Glyph {
+        width,
+        height,
+        parts,
+    }
+}
+
+fn pick_hard_blank(rows: &[(char, Vec<Vec<GlyphPart>>)]) -> Result<char> {
+    let used = |c: char| {
+        rows.iter()
+            .flat_map(|(_, r)| r.iter().flatten())
+            .any(|p| matches!(p, GlyphPart::Char(ch) | GlyphPart::AnsiChar { ch, .. } if *ch == c))
+    };
+    // '@' is the usual end mark
+    HARD_BLANK_CANDIDATES
+        .into_iter()
+        .chain(('!'..='~').filter(|c| *c != '@'))
+        .find(|c| !used(*c))
+        .ok_or(FontError::NoHardBlank)
+}
+
+/// Font types [`convert`] converts between.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontKind {
+    /// FIGlet `.flf`.
+    Figlet,
+    /// TOIlet `.tlf`, keeps colors.
+    Tlf,
+    TdfBlock,
+    TdfOutline,
+    TdfColor,
+}
+
+impl FontKind {
+    /// Kind of a loaded font.
+    pub fn of(font: &Font) -> Self {
+        match font {
+            Font::Figlet(f) if f.format == FigletFormat::Tlf => FontKind::Tlf,
+            Font::Figlet(_) => FontKind::Figlet,
+            Font::Tdf(t) => match t.font_type {
+                TdfFontType::Block => FontKind::TdfBlock,
+                TdfFontType::Outline => FontKind::TdfOutline,
+                TdfFontType::Color => FontKind::TdfColor,
+            },
+        }
+    }
+
+    /// Parse a kind name as used on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "figlet" | "flf" => Some(FontKind::Figlet),
+            "tlf" | "toilet" => Some(FontKind::Tlf),
+            "block" => Some(FontKind::TdfBlock),
+            "outline" => Some(FontKind::TdfOutline),
+            "color" => Some(FontKind::TdfColor),
+            _ => None,
+        }
+    }
+
+    fn tdf_type(self) -> Option<TdfFontType> {
+        match self {
+            FontKind::Figlet | FontKind::Tlf => None,
+            FontKind::TdfBlock => Some(TdfFontType::Block),
+            FontKind::TdfOutline => Some(TdfFontType::Outline),
+            FontKind::TdfColor => Some(TdfFontType::Color),
+        }
+    }
+}
+
+impl fmt::Display for FontKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FontKind::Figlet => "FIGlet",
+            FontKind::Tlf => "TOIlet",
+            FontKind::TdfBlock => "TDF block",
+            FontKind::TdfOutline => "TDF outline",
+            FontKind::TdfColor => "TDF color",
+        })
+    }
+}
+
+/// Why a glyph was left out of a converted font.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DropReason {
+    /// TDF fonts only hold `!` through `~`.
+    OutOfRange,
+    /// The glyph exceeds the TDF size limits.
+    TooLarge { width: usize, height: usize },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DroppedGlyph {
+    pub ch: char,
+    pub reason: DropReason,
+}
+
+/// A glyph that was cut down to fit the target size limits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClippedGlyph {
+    pub ch: char,
+    /// Original (width, height).
+    pub from: (usize, usize),
+    /// Converted (width, height).
+    pub to: (usize, usize),
+}
+
+/// What a [`convert`] call could not carry over.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    pub dropped: Vec<DroppedGlyph>,
+    pub clipped: Vec<ClippedGlyph>,
+    /// Distinct characters without an exact CP437 equivalent that TDF files store as their
+    /// nearest equivalent, see [`crate::cp437::transliterate`].
+    pub transliterated: Vec<char>,
+    /// Distinct characters the target cannot store (written as `?` in TDF files).
+    pub unmappable: Vec<char>,
+    /// Glyphs whose colors were dropped.
+    pub lost_colors: Vec<char>,
+    /// Glyphs that could not be traced as outlines and were drawn with full blocks.
+    pub block_fallback: Vec<char>,
+    /// Original font name, if it was truncated to fit the TDF name field.
+    pub truncated_name: Option<String>,
+}
+
+impl ConversionReport {
+    /// `true` if nothing was dropped, clipped or changed.
+    pub fn is_lossless(&self) -> bool {
+        *self == ConversionReport::default()
+    }
+}
+
+impl fmt::Display for ConversionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn chars(list: &[char]) -> String {
+            list.iter()
+                .map(|c| format!("{c:?}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+        if let Some(name) = &self.truncated_name {
+            writeln!(
+                f,
+                "Font name {name:?} truncated to {MAX_TDF_NAME_LEN} bytes"
+            )?;
+        }
+        if !self.dropped.is_empty() {
+            let list: Vec<String> = self
+                .dropped
+                .iter()
+                .map(|d| match d.reason {
+                    DropReason::OutOfRange => format!("{:?} (outside ! to ~)", d.ch),
+                    DropReason::TooLarge { width, height } => format!(
+                        "{:?} ({width}x{height} exceeds {MAX_TDF_GLYPH_WIDTH}x{MAX_TDF_GLYPH_HEIGHT})",
+                        d.ch
+                    ),
+                })
+                .collect();
+            writeln!(f, "Dropped {} glyph(s): {}", list.len(), list.join(", "))?;
+        }
+        if !self.clipped.is_empty() {
+            let list: Vec<String> = self
+                .clipped
+                .iter()
+                .map(|c| {
+                    format!(
+                        "{:?} ({}x{} -> {}x{})",
+                        c.ch, c.from.0, c.from.1, c.to.0, c.to.1
+                    )
+                })
+                .collect();
+            writeln!(f, "Clipped {} glyph(s): {}", list.len(), list.join(", "))?;
+        }
+        if !self.transliterated.is_empty() {
+            writeln!(
+                f,
+                "{} character(s) replaced by their nearest CP437 equivalent: {}",
+                self.transliterated.len(),
+                chars(&self.transliterated)
+            )?;
+        }
+        if !self.unmappable.is_empty() {
+            writeln!(
+                f,
+                "{} character(s) have no CP437 equivalent: {}",
+                self.unmappable.len(),
+                chars(&self.unmappable)
+            )?;
+        }
+        if !self.lost_colors.is_empty() {
+            writeln!(
+                f,
+                "Colors dropped from {} glyph(s): {}",
+                self.lost_colors.len(),
+                chars(&self.lost_colors)
+            )?;
+        }
+        if !self.block_fallback.is_empty() {
+            writeln!(
+                f,
+                "{} glyph(s) could not be traced as outlines and were drawn with full blocks: {}",
+                self.block_fallback.len(),
+                chars(&self.block_fallback)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Options for [`convert`].
+#[derive(Clone, Debug, Default)]
+pub struct ConvertOptions {
+    /// Outline style (0-18) baked in when converting from an Outline font.
+    pub outline_style: usize,
+    /// How FIGlet glyphs over the TDF size limits are handled, see [`fit_to_tdf`].
+    pub fit: GlyphFit,
+}
+
+/// A converted font and what was lost on the way.
+pub struct Conversion {
+    pub font: Font,
+    /// The lower bands of a font split with [`GlyphFit::Split`]; `font` holds the top band.
+    pub split_fonts: Vec<Font>,
+    pub report: ConversionReport,
+}
+
+/// Convert a font into another [`FontKind`].
+///
+/// Converting to the font's own kind returns a copy. Outline fonts are baked with
+/// [`ConvertOptions::outline_style`] when converted to anything else, and other fonts are
+/// traced (see [`figlet_to_outline_tdf`]) when converted to Outline.
+///
+/// # Errors
+///
+/// Returns [`FontError::ConversionIncompatible`] if no glyph can be converted.
+pub fn convert(font: &Font, target: FontKind, options: &ConvertOptions) -> Result<Conversion> {
+    let mut report = ConversionReport::default();
+    let mut split_fonts = Vec::new();
+    let converted = match (font, target.tdf_type()) {
+        (Font::Figlet(fig), None) => {
+            let mut fig = fig.clone();
+            fig.format = if target == FontKind::Tlf {
+                FigletFormat::Tlf
+            } else {
+                FigletFormat::Flf
+            };
+            if target == FontKind::Figlet {
+                let stripped: Vec<(char, Glyph)> = fig
+                    .iter_glyphs()
+                    .filter_map(|(ch, g)| strip_colors(g).map(|g| (ch, g)))
+                    .collect();
+                for (ch, g) in stripped {
+                    report.lost_colors.push(ch);
+                    fig.add_glyph(ch, g);
+                }
+            }
+            Font::Figlet(fig)
+        }
+        (Font::Figlet(fig), Some(ty)) => {
+            let fitted = fit_to_tdf(fig, options.fit);
+            report.clipped = fitted.clipped;
+            // TDF fonts draw spaces from their spacing
+            for (ch, g) in fig.iter_glyphs().filter(|(ch, _)| *ch != ' ') {
+                let too_large =
+                    |g: &Glyph| g.width > MAX_TDF_GLYPH_WIDTH || g.height > MAX_TDF_GLYPH_HEIGHT;
+                let reason = if !(TDF_FIRST_CHAR..=TDF_LAST_CHAR).contains(&ch) {
+                    DropReason::OutOfRange
+                } else if fitted.fonts[0].glyph(ch).is_some_and(too_large) {
+                    DropReason::TooLarge {
+                        width: g.width,
+                        height: g.height,
                     }
-                    GlyphPart::FillMarker => {
-                        parts.push(GlyphPart::FillMarker);
-                        line_width += 1;
+                } else {
+                    if ty != TdfFontType::Color && strip_colors(g).is_some() {
+                        report.lost_colors.push(ch);
                     }
-                    GlyphPart::OutlineHole => {
-                        parts.push(GlyphPart::OutlineHole);
-                        line_width += 1;
+                    continue;
+                };
+                report.dropped.push(DroppedGlyph { ch, reason });
+            }
+            let mut fonts = Vec::with_capacity(fitted.fonts.len());
+            for part in &fitted.fonts {
+                fonts.push(Font::Tdf(if ty == TdfFontType::Outline {
+                    let conversion = figlet_to_outline_tdf(part)?;
+                    for ch in conversion.block_fallback {
+                        if !report.block_fallback.contains(&ch) {
+                            report.block_fallback.push(ch);
+                        }
                     }
-                    GlyphPart::OutlinePlaceholder(letter) => {
-                        parts.push(GlyphPart::OutlinePlaceholder(*letter));
-                        line_width += 1;
+                    conversion.font
+                } else {
+                    figlet_to_tdf(part, ty)?
+                }));
+            }
+            if fonts.len() > 1 && !fonts[0].name().starts_with(fig.name.as_str()) {
+                report.truncated_name = Some(fig.name.clone());
+            }
+            let first = fonts.remove(0);
+            split_fonts = fonts;
+            first
+        }
+        (Font::Tdf(tdf), None) => {
+            let keep_colors = target == FontKind::Tlf;
+            if !keep_colors {
+                report.lost_colors = glyphs_with_colors(tdf);
+            }
+            let mut fig = tdf_to_figlet(
+                tdf,
+                &FigletExportOptions {
+                    outline_style: options.outline_style,
+                    keep_colors,
+                },
+            )?;
+            if keep_colors {
+                fig.format = FigletFormat::Tlf;
+            }
+            Font::Figlet(fig)
+        }
+        (Font::Tdf(tdf), Some(ty)) => Font::Tdf(match (tdf.font_type, ty) {
+            (from, to) if from == to => tdf.clone(),
+            (TdfFontType::Outline, _) => bake_outline(
+                tdf,
+                &OutlineBakeOptions {
+                    outline_style: options.outline_style,
+                    target_type: ty,
+                    ..Default::default()
+                },
+            )?,
+            (_, TdfFontType::Outline) => {
+                report.lost_colors = glyphs_with_colors(tdf);
+                let conversion = trace_glyphs(
+                    TdfFont::new(tdf.name.clone(), TdfFontType::Outline, tdf.spacing),
+                    tdf.iter_glyphs(),
+                );
+                report.block_fallback = conversion.block_fallback;
+                conversion.font
+            }
+            (_, TdfFontType::Block) => {
+                report.lost_colors = glyphs_with_colors(tdf);
+                map_tdf_parts(tdf, TdfFontType::Block, |part| match part {
+                    GlyphPart::AnsiChar { ch, .. } if *ch == CP437_TO_UNICODE[0xFF] => {
+                        GlyphPart::HardBlank
                     }
-                    GlyphPart::Skip => {
-                        parts.push(GlyphPart::Skip);
-                        line_width += 1;
+                    GlyphPart::AnsiChar { ch, .. } => GlyphPart::Char(*ch),
+                    other => other.clone(),
+                })
+            }
+            (_, TdfFontType::Color) => map_tdf_parts(tdf, TdfFontType::Color, |part| match part {
+                GlyphPart::Char(ch) => GlyphPart::AnsiChar {
+                    ch: *ch,
+                    fg: 7,
+                    bg: 0,
+                    blink: false,
+                },
+                other => other.clone(),
+            }),
+        }),
+    };
+
+    let converted = match converted {
+        Font::Tdf(mut tdf) => {
+            if tdf.name.len() > MAX_TDF_NAME_LEN {
+                let mut end = MAX_TDF_NAME_LEN;
+                while !tdf.name.is_char_boundary(end) {
+                    end -= 1;
+                }
+                report.truncated_name = Some(tdf.name.clone());
+                tdf.name.truncate(end);
+            }
+            let fonts = std::iter::once(&tdf).chain(split_fonts.iter().filter_map(|f| match f {
+                Font::Tdf(t) => Some(t),
+                Font::Figlet(_) => None,
+            }));
+            for font in fonts {
+                let saved = font.to_bytes_with(Cp437Options {
+                    policy: UnmappablePolicy::Report,
+                    ..Default::default()
+                })?;
+                for (from, to) in [
+                    (saved.transliterated, &mut report.transliterated),
+                    (saved.unmappable, &mut report.unmappable),
+                ] {
+                    for ch in from {
+                        if !to.contains(&ch) {
+                            to.push(ch);
+                        }
                     }
                 }
             }
-
-            width = width.max(line_width);
-            let glyph = Glyph {
-                width,
-                height: lines,
-                parts,
-            };
-            tdf.add_glyph(code, glyph);
+            Font::Tdf(tdf)
         }
+        fig => fig,
+    };
+    Ok(Conversion {
+        font: converted,
+        split_fonts,
+        report,
+    })
+}
+
+/// Copy of `g` without colors, or `None` if it has none.
+fn strip_colors(g: &Glyph) -> Option<Glyph> {
+    if !g
+        .parts
+        .iter()
+        .any(|p| matches!(p, GlyphPart::AnsiChar { .. }))
+    {
+        return None;
     }
+    let parts = g
+        .parts
+        .iter()
+        .map(|p| match p {
+            GlyphPart::AnsiChar { ch, .. } => GlyphPart::Char(*ch),
+            other => other.clone(),
+        })
+        .collect();
+    Some(Glyph {
+        width: g.width,
+        height: g.height,
+        parts,
+    })
+}
 
-    Ok(tdf)
+fn glyphs_with_colors(tdf: &TdfFont) -> Vec<char> {
+    tdf.iter_glyphs()
+        .filter(|(_, g)| {
+            g.parts
+                .iter()
+                .any(|p| matches!(p, GlyphPart::AnsiChar { .. }))
+        })
+        .map(|(ch, _)| ch)
+        .collect()
+}
+
+fn map_tdf_parts(
+    tdf: &TdfFont,
+    font_type: TdfFontType,
+    map: impl Fn(&GlyphPart) -> GlyphPart,
+) -> TdfFont {
+    let mut out = TdfFont::new(tdf.name.clone(), font_type, tdf.spacing);
+    for (ch, g) in tdf.iter_glyphs() {
+        out.add_glyph(
+            ch,
+            Glyph {
+                width: g.width,
+                height: g.height,
+                parts: g.parts.iter().map(&map).collect(),
+            },
+        );
+    }
+    out
 }
@@ -0,0 +1,191 @@
+//! Mapping Unicode characters to CP437 bytes for TDF files.
+//!
+//! TDF glyphs are stored as CP437. Characters with an exact equivalent are mapped through
+//! [`UNICODE_TO_CP437`]; [`transliterate`] finds a close substitute for common characters
+//! that CP437 lacks (rounded and heavy box drawing, quadrant blocks, smart quotes, accented
+//! letters, ...). [`UnmappablePolicy`] decides what happens to the rest.
+use crate::{
+    error::{FontError, Result},
+    tdf::UNICODE_TO_CP437,
+};
+
+/// Base letters for Latin Extended-A (U+0100..=U+017F).
+const LATIN_EXTENDED_A: &str = concat!(
+    "AaAaAaCcCcCcCcDd",
+    "DdEeEeEeEeEeGgGg",
+    "GgGgHhHhIiIiIiIi",
+    "IiIiJjKkkLlLlLlL",
+    "lLlNnNnNnnNnOoOo",
+    "OoOoRrRrRrSsSsSs",
+    "SsTtTtTtUuUuUuUu",
+    "UuUuWwYyYZzZzZzs",
+);
+
+/// The nearest CP437 character for `ch`, if it has no exact equivalent but a close one.
+///
+/// Returns `None` for characters CP437 can store as they are and for characters without a
+/// reasonable substitute.
+pub fn transliterate(ch: char) -> Option<char> {
+    if UNICODE_TO_CP437.contains_key(&ch) {
+        return None;
+    }
+    let sub = match ch {
+        // Rounded, heavy and dashed box drawing
+        '╭' | '┏' | '┍' | '┎' => '┌',
+        '╮' | '┓' | '┑' | '┒' => '┐',
+        '╰' | '┗' | '┕' | '┖' => '└',
+        '╯' | '┛' | '┙' | '┚' => '┘',
+        '┣' | '┝' | '┠' => '├',
+        '┫' | '┥' | '┨' => '┤',
+        '┳' | '┯' | '┰' => '┬',
+        '┻' | '┷' | '┸' => '┴',
+        '╋' | '┿' | '╂' => '┼',
+        '━' | '┄' | '┅' | '┈' | '┉' | '╌' | '╍' | '╴' | '╶' | '╸' | '╺' | '╼' | '╾' => {
+            '─'
+        }
+        '┃' | '┆' | '┇' | '┊' | '┋' | '╎' | '╏' | '╵' | '╷' | '╹' | '╻' | '╽' | '╿' => {
+            '│'
+        }
+        '╱' => '/',
+        '╲' => '\\',
+        '╳' => 'X',
+        // Partial and quadrant blocks
+        '▘' | '▝' | '▔' => '▀',
+        '▖' | '▗' | '▂' | '▃' | '▅' => '▄',
+        '▁' => '_',
+        '▆' | '▇' | '▉' | '▊' | '▛' | '▜' | '▙' | '▟' => '█',
+        '▎' | '▍' | '▋' => '▌',
+        '▏' | '▕' => '│',
+        '▚' | '▞' => '▒',
+        '▪' | '◼' | '◾' => '■',
+        '●' | '◦' | '∘' => '•',
+        '▶' | '▸' => '►',
+        '◀' | '◂' => '◄',
+        // Punctuation
+        '‘' | '’' | '‚' | '‛' | '′' | '´' => '\'',
+        '“' | '”' | '„' | '‟' | '″' | '¨' => '"',
+        '‹' => '<',
+        '›' => '>',
+        '‐' | '‑' | '‒' | '–' | '—' | '―' | '−' => '-',
+        '…' => '.',
+        '¦' => '|',
+        '¸' => ',',
+        '×' => 'x',
+        '©' => 'c',
+        '®' => 'R',
+        '¹' => '1',
+        '³' => '3',
+        '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}' => ' ',
+        // Latin-1 letters without a CP437 equivalent
+        'À' | 'Á' | 'Â' | 'Ã' => 'A',
+        'ã' => 'a',
+        'È' | 'Ê' | 'Ë' => 'E',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'Ð' => 'D',
+        'ð' => 'd',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ø' => 'O',
+        'õ' | 'ø' => 'o',
+        'Ù' | 'Ú' | 'Û' => 'U',
+        'Ý' => 'Y',
+        'ý' => 'y',
+        '\u{0100}'..='\u{017F}' => LATIN_EXTENDED_A
+            .chars()
+            .nth(ch as usize - 0x100)
+            .unwrap_or('?'),
+        _ => return None,
+    };
+    Some(sub)
+}
+
+/// What to do with characters that have neither an exact nor a transliterated CP437
+/// equivalent.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnmappablePolicy {
+    /// Fail with [`FontError::Unmappable`].
+    Error,
+    /// Write `?` without further notice.
+    #[default]
+    Substitute,
+    /// Write `?` and list the characters in [`Cp437Encoder::unmappable`].
+    Report,
+}
+
+/// Settings for writing CP437.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cp437Options {
+    /// Replace characters without an exact equivalent by their [`transliterate`]d form.
+    pub transliterate: bool,
+    pub policy: UnmappablePolicy,
+}
+
+impl Default for Cp437Options {
+    fn default() -> Self {
+        Self {
+            transliterate: true,
+            policy: UnmappablePolicy::default(),
+        }
+    }
+}
+
+/// Maps characters to CP437 bytes according to [`Cp437Options`], remembering what it had to
+/// replace.
+#[derive(Clone, Debug, Default)]
+pub struct Cp437Encoder {
+    pub options: Cp437Options,
+    /// Distinct characters written as their transliteration, in order of first appearance.
+    /// Only filled with [`UnmappablePolicy::Report`].
+    pub transliterated: Vec<char>,
+    /// Distinct characters written as `?`, in order of first appearance. Only filled with
+    /// [`UnmappablePolicy::Report`].
+    pub unmappable: Vec<char>,
+}
+
+impl Cp437Encoder {
+    pub fn new(options: Cp437Options) -> Self {
+        Self {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// CP437 byte for `ch`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FontError::Unmappable`] with [`UnmappablePolicy::Error`] if `ch` cannot be
+    /// represented.
+    pub fn encode(&mut self, ch: char) -> Result<u8> {
+        if let Some(&b) = UNICODE_TO_CP437.get(&ch) {
+            return Ok(b);
+        }
+        let report = self.options.policy == UnmappablePolicy::Report;
+        if self.options.transliterate {
+            if let Some(&b) = transliterate(ch).and_then(|t| UNICODE_TO_CP437.get(&t)) {
+                if report && !self.transliterated.contains(&ch) {
+                    self.transliterated.push(ch);
+                }
+                return Ok(b);
+            }
+        }
+        match self.options.policy {
+            UnmappablePolicy::Error => Err(FontError::Unmappable(ch)),
+            UnmappablePolicy::Substitute => Ok(b'?'),
+            UnmappablePolicy::Report => {
+                if !self.unmappable.contains(&ch) {
+                    self.unmappable.push(ch);
+                }
+                Ok(b'?')
+            }
+        }
+    }
+}
+
+/// CP437 byte for `ch`, exact or transliterated.
+pub fn to_cp437(ch: char) -> Option<u8> {
+    UNICODE_TO_CP437
+        .get(&ch)
+        .or_else(|| transliterate(ch).and_then(|t| UNICODE_TO_CP437.get(&t)))
+        .copied()
+}
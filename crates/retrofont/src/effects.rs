@@ -0,0 +1,216 @@
+//! Effects drawn around rendered text: drop shadows, outlines and 3D extrusion.
+//!
+//! Effects work on a rendered [`Canvas`] and follow the shape of the text: they start from
+//! the cells with ink (see [`is_ink`]) and only draw onto positions without ink, so
+//! transparent cells of a glyph stay see-through and the letters themselves are never
+//! covered. Apply them after colorizing, otherwise the colorizer paints uncolored effect
+//! cells like the text.
+use crate::{canvas::Canvas, glyph::transform_outline, Cell, Color, Palette};
+
+/// Whether a canvas position belongs to the text: drawn and not a blank space.
+pub fn is_ink(cell: Option<&Cell>) -> bool {
+    cell.is_some_and(|c| c.ch != ' ' || c.bg.is_some_and(|bg| bg != 0))
+}
+
+/// A copy of the text, offset and drawn in one character, behind the text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shadow {
+    /// Columns to the right and rows down.
+    pub offset: (usize, usize),
+    /// A shade (`░`, `▒`, `▓`), or `█` for a solid shadow in `color`.
+    pub ch: char,
+    /// `None` leaves the shadow uncolored.
+    pub color: Option<Color>,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        // Text mode cells are about twice as tall as wide.
+        Self {
+            offset: (2, 1),
+            ch: '░',
+            color: None,
+        }
+    }
+}
+
+impl Shadow {
+    pub fn apply(&self, canvas: &mut Canvas, palette: &Palette) {
+        let ink = ink_mask(canvas);
+        let cell = effect_cell(self.ch, self.color, palette);
+        cast(canvas, &ink, self.offset, &cell);
+    }
+}
+
+/// An outline around the text, drawn with one of the TDF outline styles
+/// ([`crate::OUTLINE_CHAR_SET_UNICODE`]).
+///
+/// The outline runs through the positions next to the ink (diagonals included), so it traces
+/// each letter and the holes inside them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Border {
+    /// Outline style index (0..18).
+    pub style: usize,
+    pub color: Option<Color>,
+}
+
+impl Border {
+    /// Draw the outline. If the ink touches the left or top edge the canvas is shifted by one
+    /// position to make room.
+    pub fn apply(&self, canvas: &mut Canvas, palette: &Palette) {
+        let ink = ink_mask(canvas);
+        let dx = ink.iter().any(|row| row.first() == Some(&true)) as usize;
+        let dy = ink.first().is_some_and(|row| row.contains(&true)) as usize;
+        canvas.shift(dx, dy);
+        let ink = ink_mask(canvas);
+
+        let ink_at = |x: isize, y: isize| {
+            x >= 0
+                && y >= 0
+                && ink
+                    .get(y as usize)
+                    .and_then(|row| row.get(x as usize))
+                    .copied()
+                    .unwrap_or(false)
+        };
+        let ring = |x: isize, y: isize| {
+            !ink_at(x, y) && (-1..=1).any(|dy| (-1..=1).any(|dx| ink_at(x + dx, y + dy)))
+        };
+        // Ink in the row below, from column `x - 1` to `x + 1`
+        let ink_below = |x: isize, y: isize| (-1..=1).any(|dx| ink_at(x + dx, y + 1));
+
+        let width = ink.iter().map(Vec::len).max().unwrap_or(0) as isize;
+        let mut border = Vec::new();
+        for y in 0..=ink.len() as isize {
+            for x in 0..=width {
+                if !ring(x, y) {
+                    continue;
+                }
+                let arms = (
+                    ring(x, y - 1),
+                    ring(x + 1, y),
+                    ring(x, y + 1),
+                    ring(x - 1, y),
+                );
+                // Corners take the edge type (top A or bottom B) of their horizontal arm.
+                let letter = match arms {
+                    (false, true, false, true) => top_or_bottom(ink_below(x, y)),
+                    (true, false, true, false) => left_or_right(ink_at(x + 1, y)),
+                    (false, true, true, false) => corner(b'E', ink_below(x + 1, y)),
+                    (false, false, true, true) => corner(b'F', ink_below(x - 1, y)),
+                    (true, true, false, false) => corner(b'I', ink_below(x + 1, y)),
+                    (true, false, false, true) => corner(b'J', ink_below(x - 1, y)),
+                    (true, false, true, true) => b'M',
+                    (true, true, true, false) => b'N',
+                    _ if ink_at(x - 1, y) || ink_at(x + 1, y) => left_or_right(ink_at(x + 1, y)),
+                    _ => top_or_bottom(ink_below(x, y)),
+                };
+                border.push((x as usize, y as usize, letter));
+            }
+        }
+        for (x, y, letter) in border {
+            let ch = transform_outline(self.style, letter);
+            canvas.set(x, y, Some(effect_cell(ch, self.color, palette)));
+        }
+    }
+}
+
+/// Layers of copies behind the text, each one step further away, for a 3D look.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extrusion {
+    /// Number of layers.
+    pub depth: usize,
+    /// Columns to the right and rows down per layer.
+    pub step: (usize, usize),
+    pub ch: char,
+    /// Color of each layer from front to back; the last color repeats for deeper layers.
+    pub colors: Vec<Color>,
+}
+
+impl Default for Extrusion {
+    fn default() -> Self {
+        Self {
+            depth: 2,
+            step: (1, 1),
+            ch: '▓',
+            colors: vec![Color::Index(8)],
+        }
+    }
+}
+
+impl Extrusion {
+    pub fn apply(&self, canvas: &mut Canvas, palette: &Palette) {
+        let ink = ink_mask(canvas);
+        // Back to front, so nearer layers cover deeper ones.
+        for layer in (1..=self.depth).rev() {
+            let color = self.colors.get(layer - 1).or(self.colors.last()).copied();
+            let cell = effect_cell(self.ch, color, palette);
+            cast(
+                canvas,
+                &ink,
+                (self.step.0 * layer, self.step.1 * layer),
+                &cell,
+            );
+        }
+    }
+}
+
+fn ink_mask(canvas: &Canvas) -> Vec<Vec<bool>> {
+    canvas
+        .lines()
+        .iter()
+        .map(|line| line.iter().map(|c| is_ink(c.as_ref())).collect())
+        .collect()
+}
+
+/// Draw `cell` at every ink position moved by `offset` that has no ink itself.
+fn cast(canvas: &mut Canvas, ink: &[Vec<bool>], offset: (usize, usize), cell: &Cell) {
+    let has_ink = |x: usize, y: usize| ink.get(y).and_then(|row| row.get(x)) == Some(&true);
+    for (y, row) in ink.iter().enumerate() {
+        for (x, _) in row.iter().enumerate().filter(|(_, ink)| **ink) {
+            let (tx, ty) = (x + offset.0, y + offset.1);
+            if !has_ink(tx, ty) {
+                canvas.set(tx, ty, Some(*cell));
+            }
+        }
+    }
+}
+
+fn effect_cell(ch: char, color: Option<Color>, palette: &Palette) -> Cell {
+    let mut cell = Cell::new(ch, None, None, false);
+    (cell.fg, cell.fg_rgb) = match color {
+        None => (None, None),
+        Some(Color::Index(i)) => (Some(i), None),
+        Some(Color::Rgb(c)) => (Some(palette.nearest(c)), Some(c)),
+    };
+    cell
+}
+
+fn top_or_bottom(ink_below: bool) -> u8 {
+    if ink_below {
+        b'A'
+    } else {
+        b'B'
+    }
+}
+
+fn left_or_right(ink_right: bool) -> u8 {
+    if ink_right {
+        b'C'
+    } else {
+        b'D'
+    }
+}
+
+/// `E`, `F`, `I` or `J` for a corner joining a top edge, the letter two further for one
+/// joining a bottom edge.
+fn corner(top: u8, top_edge: bool) -> u8 {
+    if top_edge {
+        top
+    } else {
+        top + 2
+    }
+}
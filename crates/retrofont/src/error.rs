@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -13,16 +15,18 @@ pub enum FontError {
     FigletGzipNotSupported,
     #[error("FIGlet: missing or invalid header")]
     FigletMissingHeader,
-    #[error("FIGlet: not a flf2a header")]
+    #[error("FIGlet: not a flf2a or tlf2a header")]
     FigletInvalidSignature,
     #[error("FIGlet: incomplete header")]
     FigletIncompleteHeader,
     #[error("FIGlet: missing height in header")]
     FigletMissingHeight,
-    #[error("FIGlet: incomplete character definition")]
-    FigletIncompleteChar,
-    #[error("FIGlet: character line missing @ marker")]
-    FigletMissingMarker,
+    #[error("FIGlet: incomplete definition of {ch:?} at line {line}")]
+    FigletIncompleteChar { line: usize, ch: char },
+    #[error("FIGlet: line {line} of {ch:?} is missing the @ marker")]
+    FigletMissingMarker { line: usize, ch: char },
+    #[error("FIGlet: invalid UTF-8 at line {line} (byte {offset})")]
+    FigletInvalidUtf8 { line: usize, offset: usize },
 
     // ZIP archive errors
     #[error("ZIP: {0}")]
@@ -39,18 +43,52 @@ pub enum FontError {
     TdfIdMismatch,
     #[error("TDF: missing CTRL-Z marker")]
     TdfMissingCtrlZ,
-    #[error("TDF: font indicator mismatch")]
-    TdfFontIndicatorMismatch,
-    #[error("TDF: unsupported font type: {0}")]
-    TdfUnsupportedType(u8),
-    #[error("TDF: truncated data at {field}")]
-    TdfTruncated { field: &'static str },
-    #[error("TDF: glyph offset {offset} exceeds block size {size}")]
-    TdfGlyphOutOfBounds { offset: usize, size: usize },
+    #[error("TDF: font indicator mismatch at byte {offset}")]
+    TdfFontIndicatorMismatch { offset: usize },
+    #[error("TDF: unsupported font type {font_type} at byte {offset}")]
+    TdfUnsupportedType { font_type: u8, offset: usize },
+    #[error("TDF: truncated data at {field} (byte {offset})")]
+    TdfTruncated { field: &'static str, offset: usize },
+    #[error("TDF: glyph {ch:?} offset {offset} exceeds block size {size}")]
+    TdfGlyphOutOfBounds {
+        ch: char,
+        offset: usize,
+        size: usize,
+    },
+    #[error("TDF: glyph {ch:?} ends inside a color cell at byte {offset}")]
+    TdfGlyphTruncated { ch: char, offset: usize },
+    #[error("TDF: glyph {ch:?} at byte {offset} has no terminator")]
+    TdfGlyphUnterminated { ch: char, offset: usize },
+    #[error(
+        "TDF: glyph {ch:?} is {}x{}, its header says {}x{}",
+        .actual.0, .actual.1, .declared.0, .declared.1
+    )]
+    TdfGlyphSizeMismatch {
+        ch: char,
+        declared: (usize, usize),
+        actual: (usize, usize),
+    },
+    #[error(
+        "TDF: glyph {ch:?} is {width}x{height}, the format allows at most {}x{}",
+        crate::tdf::MAX_TDF_GLYPH_WIDTH,
+        crate::tdf::MAX_TDF_GLYPH_HEIGHT
+    )]
+    TdfGlyphTooLarge {
+        ch: char,
+        width: usize,
+        height: usize,
+    },
+    #[error("TDF: {len} unknown bytes after the last font at byte {offset}")]
+    TdfTrailingBytes { offset: usize, len: usize },
     #[error("TDF: bundle contains no fonts")]
     TdfEmptyBundle,
     #[error("TDF: name too long ({len} bytes, max {max})")]
     TdfNameTooLong { len: usize, max: usize },
+    #[error("TDF: font index {index} out of range (bundle has {len} fonts)")]
+    TdfFontIndexOutOfRange { index: usize, len: usize },
+    /// An error in the font at `index` (0-based) of a bundle.
+    #[error("TDF font #{}: {inner}", .index + 1)]
+    TdfBundleFont { index: usize, inner: Box<FontError> },
 
     // Format detection
     #[error("unrecognized font format")]
@@ -61,6 +99,10 @@ pub enum FontError {
     // Conversion errors
     #[error("FIGlet font is not compatible with TDF conversion")]
     ConversionIncompatible,
+    #[error("no CP437 equivalent for {0:?}")]
+    Unmappable(char),
+    #[error("FIGlet: every printable ASCII character is used, none is free for the hard blank")]
+    NoHardBlank,
 
     // Rendering errors
     #[error("unsupported font type")]
@@ -70,9 +112,45 @@ pub enum FontError {
     #[error("unknown character: {0}")]
     UnknownChar(char),
 
+    // Palette errors
+    #[error("invalid palette: {0}")]
+    InvalidPalette(String),
+
     // UTF-8 errors
     #[error("UTF-8 error: {0}")]
     Utf8(#[from] std::str::Utf8Error),
+
+    /// An error while loading the file at `path`.
+    #[error("{}: {inner}", .path.display())]
+    File {
+        path: PathBuf,
+        inner: Box<FontError>,
+    },
+}
+
+impl FontError {
+    /// The error without the file and bundle context added by [`FontError::File`] and
+    /// [`FontError::TdfBundleFont`].
+    pub fn root(&self) -> &FontError {
+        match self {
+            FontError::File { inner, .. } | FontError::TdfBundleFont { inner, .. } => inner.root(),
+            other => other,
+        }
+    }
+
+    pub(crate) fn in_file(self, path: &Path) -> Self {
+        FontError::File {
+            path: path.to_path_buf(),
+            inner: Box::new(self),
+        }
+    }
+
+    pub(crate) fn in_bundle_font(self, index: usize) -> Self {
+        FontError::TdfBundleFont {
+            index,
+            inner: Box::new(self),
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, FontError>;
@@ -1,7 +1,8 @@
-//! FIGlet font placeholder.
+//! FIGlet (`.flf`) and TOIlet (`.tlf`) fonts.
 use crate::{
     error::{FontError, Result},
     glyph::{Glyph, GlyphPart},
+    validate::{check_glyph, Diagnostic, Issue},
 };
 use std::io::{Cursor, Read};
 use std::ops::Range;
@@ -9,12 +10,39 @@ use std::sync::{Arc, OnceLock};
 use std::{fs, path::Path};
 use zip::ZipArchive;
 
+/// File flavor of a [`FigletFont`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FigletFormat {
+    /// FIGlet `flf2a`.
+    #[default]
+    Flf,
+    /// TOIlet `tlf2a`: UTF-8 glyphs, optionally colored with ANSI escapes.
+    Tlf,
+}
+
+/// How a FIGlet font places glyphs next to each other.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FigletLayout {
+    /// Glyphs are placed at their full width, whitespace included.
+    #[default]
+    FullWidth,
+    /// Glyphs are moved together until they touch.
+    Kerning,
+    /// Glyphs overlap by one column where the smushing rules allow it.
+    Smushing,
+}
+
 #[derive(Clone)]
 pub struct FigletFont {
     pub name: String,
+    pub format: FigletFormat,
     pub header: String,
     pub comments: Vec<String>,
     pub hard_blank: char,
+    /// Rows from the top of a glyph to the baseline; `None` means the full glyph height.
+    pub baseline: Option<usize>,
     // Programmatic/converted glyphs live here.
     glyphs_overlay: [Option<Glyph>; 256],
     // Parsed glyphs are decoded on-demand.
@@ -35,20 +63,50 @@ struct LazyFigletSource {
     cache: Arc<[OnceLock<Glyph>; 256]>,
     // Precomputed spacing hint (average max line width).
     avg_width: Option<usize>,
+    // Character, line number and byte offset of the glyph line without end mark that ended
+    // parsing early.
+    missing_marker: Option<(char, usize, usize)>,
 }
 
 impl FigletFont {
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
+            format: FigletFormat::Flf,
             header: String::new(),
             comments: Vec::new(),
             hard_blank: '$',
+            baseline: None,
             glyphs_overlay: std::array::from_fn(|_| None),
             lazy: None,
         }
     }
 
+    /// Horizontal layout from the header: the `full_layout` field if present, else
+    /// `old_layout`. Fonts without a header use [`FigletLayout::FullWidth`].
+    pub fn layout(&self) -> FigletLayout {
+        let fields: Vec<i32> = self
+            .header
+            .split_whitespace()
+            .map(|f| f.parse().unwrap_or(0))
+            .collect();
+        if let Some(full) = fields.get(7) {
+            if full & 128 != 0 {
+                FigletLayout::Smushing
+            } else if full & 64 != 0 {
+                FigletLayout::Kerning
+            } else {
+                FigletLayout::FullWidth
+            }
+        } else {
+            match fields.get(4) {
+                Some(0) => FigletLayout::Kerning,
+                Some(old) if *old > 0 => FigletLayout::Smushing,
+                _ => FigletLayout::FullWidth,
+            }
+        }
+    }
+
     /// Safe access to a glyph by byte code (0-255).
     pub fn glyph(&self, ch: char) -> Option<&Glyph> {
         let code = ch as u32;
@@ -67,6 +125,18 @@ impl FigletFont {
         Some(lazy.cache[idx].get_or_init(|| decode_glyph(lazy, idx)))
     }
 
+    /// Add or replace a glyph (byte codes 0-255 only).
+    ///
+    /// Glyphs containing [`GlyphPart::AnsiChar`] make [`FigletFont::to_bytes`] write a colored
+    /// TOIlet (`tlf2a`) font.
+    pub fn add_glyph(&mut self, ch: char, glyph: Glyph) {
+        let code = ch as u32;
+        if code > u8::MAX as u32 {
+            return;
+        }
+        self.glyphs_overlay[code as usize] = Some(glyph);
+    }
+
     /// Iterate over all defined FIGlet glyphs as (char, &Glyph).
     pub fn iter_glyphs(&self) -> impl Iterator<Item = (char, &Glyph)> {
         (0u16..=255).filter_map(move |i| {
@@ -75,9 +145,12 @@ impl FigletFont {
         })
     }
 
+    /// Load a font file; errors name the file, see [`FontError::File`].
     pub fn load_file(path: &Path) -> Result<Self> {
-        let bytes = fs::read(path)?;
-        Self::load(&bytes)
+        fs::read(path)
+            .map_err(FontError::from)
+            .and_then(|bytes| Self::load(&bytes))
+            .map_err(|e| e.in_file(path))
     }
 
     pub fn glyph_count(&self) -> usize {
@@ -112,11 +185,7 @@ impl FigletFont {
             total += g.width;
             count += 1;
         }
-        if count == 0 {
-            None
-        } else {
-            Some(total / count)
-        }
+        total.checked_div(count)
     }
 
     pub fn load(bytes: &[u8]) -> Result<Self> {
@@ -140,7 +209,7 @@ impl FigletFont {
                 let mut file = archive
                     .by_index(i)
                     .map_err(|e| FontError::Zip(format!("entry error: {e}")))?;
-                if file.name().ends_with(".flf") {
+                if file.name().ends_with(".flf") || file.name().ends_with(".tlf") {
                     let mut buf = Vec::new();
                     file.read_to_end(&mut buf)
                         .map_err(|e| FontError::Zip(format!("read error: {e}")))?;
@@ -158,7 +227,11 @@ impl FigletFont {
 
     fn parse_bytes(bytes: Arc<[u8]>) -> Result<Self> {
         // Validate UTF-8 once; we will slice by newline boundaries thereafter.
-        let _ = std::str::from_utf8(bytes.as_ref())?;
+        if let Err(e) = std::str::from_utf8(bytes.as_ref()) {
+            let offset = e.valid_up_to();
+            let line = bytes[..offset].iter().filter(|&&b| b == b'\n').count() + 1;
+            return Err(FontError::FigletInvalidUtf8 { line, offset });
+        }
 
         let line_ranges = compute_line_ranges(bytes.as_ref());
         if line_ranges.is_empty() {
@@ -169,11 +242,15 @@ impl FigletFont {
         let header_range = line_ranges[line_idx].clone();
         let header_line = std::str::from_utf8(&bytes[header_range.clone()])?;
         line_idx += 1;
-        if !header_line.starts_with("flf2a") {
+        let format = if header_line.starts_with("flf2a") {
+            FigletFormat::Flf
+        } else if header_line.starts_with("tlf2a") {
+            FigletFormat::Tlf
+        } else {
             return Err(FontError::FigletInvalidSignature);
-        }
+        };
 
-        // Extract hard blank character (the character immediately after "flf2a")
+        // Extract hard blank character (the character immediately after the signature)
         let hard_blank = header_line.chars().nth(5).unwrap_or('$');
 
         let header_parts: Vec<&str> = header_line.split_whitespace().collect();
@@ -186,14 +263,17 @@ impl FigletFont {
             .get(1)
             .and_then(|s| s.parse().ok())
             .ok_or(FontError::FigletMissingHeight)?;
+        let baseline: Option<usize> = header_parts.get(2).and_then(|s| s.parse().ok());
         let comment_count: usize = header_parts
             .get(5)
             .and_then(|s| s.parse().ok())
             .unwrap_or(0);
 
         let mut font = FigletFont::new("figlet");
+        font.format = format;
         font.header = header_line.to_string();
         font.hard_blank = hard_blank;
+        font.baseline = baseline.filter(|b| (1..=height).contains(b));
 
         // Read comment lines
         for _ in 0..comment_count {
@@ -212,10 +292,17 @@ impl FigletFont {
         let mut glyph_line_len: [u8; 256] = [0u8; 256];
         let mut sum_width = 0usize;
         let mut count = 0usize;
+        let mut missing_marker = None;
 
         // Load required characters (ASCII 32-126) = 95 chars
         for ch in 32u8..=126u8 {
-            match read_character_ranges(&line_ranges, &mut line_idx, height, bytes.as_ref()) {
+            match read_character_ranges(
+                &line_ranges,
+                &mut line_idx,
+                height,
+                bytes.as_ref(),
+                ch as char,
+            ) {
                 Ok(ranges) => {
                     let start = glyph_lines.len();
                     let mut max_w = 0usize;
@@ -228,13 +315,19 @@ impl FigletFont {
                     sum_width += max_w;
                     count += 1;
                 }
+                // Fonts that end early or break keep the characters read so far.
+                Err(FontError::FigletMissingMarker { line, ch }) => {
+                    let offset = line_ranges[line - 1].start;
+                    missing_marker = Some((ch, line, offset));
+                    break;
+                }
                 Err(_) => break,
             }
         }
 
         // Try to load one more character (often 127 or extended chars)
         if let Ok(ranges) =
-            read_character_ranges(&line_ranges, &mut line_idx, height, bytes.as_ref())
+            read_character_ranges(&line_ranges, &mut line_idx, height, bytes.as_ref(), '\x7F')
         {
             let start = glyph_lines.len();
             let mut max_w = 0usize;
@@ -249,16 +342,20 @@ impl FigletFont {
         }
 
         // Load additional tagged characters if any remain (skip)
-        while read_character_ranges(&line_ranges, &mut line_idx, height, bytes.as_ref()).is_ok() {
+        while read_character_ranges(
+            &line_ranges,
+            &mut line_idx,
+            height,
+            bytes.as_ref(),
+            char::REPLACEMENT_CHARACTER,
+        )
+        .is_ok()
+        {
             // Tagged characters would need special handling - skip for now
         }
 
         let cache: Arc<[OnceLock<Glyph>; 256]> = Arc::new(std::array::from_fn(|_| OnceLock::new()));
-        let avg_width = if count == 0 {
-            None
-        } else {
-            Some(sum_width / count)
-        };
+        let avg_width = sum_width.checked_div(count);
         font.lazy = Some(LazyFigletSource {
             bytes,
             hard_blank,
@@ -267,11 +364,20 @@ impl FigletFont {
             glyph_line_len,
             cache,
             avg_width,
+            missing_marker,
         });
 
         Ok(font)
     }
 
+    /// The problem that ended loading early: a glyph line without `@` end mark, for example
+    /// in fonts using another end mark character. The characters before it are loaded, see
+    /// [`FigletFont::validate`].
+    pub fn parse_error(&self) -> Option<FontError> {
+        let (ch, line, _) = self.lazy.as_ref()?.missing_marker?;
+        Some(FontError::FigletMissingMarker { line, ch })
+    }
+
     pub fn add_raw_char(&mut self, ch: u8, raw_lines: &[&str]) {
         // Build parts with proper NewLine separators & compute width/height in one pass.
         let mut parts = Vec::new();
@@ -312,19 +418,84 @@ impl FigletFont {
             .is_some_and(|lazy| lazy.glyph_line_start[idx] != u32::MAX)
     }
 
+    /// Check the glyphs for inconsistencies, see [`crate::validate`].
+    ///
+    /// Diagnostics of glyphs loaded from a file carry the byte offset of their first line.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        if let Some((ch, line, offset)) = self.lazy.as_ref().and_then(|l| l.missing_marker) {
+            out.push(Diagnostic::new(
+                Issue::MissingEndMark { line },
+                Some(ch),
+                Some(offset),
+            ));
+        }
+        let height: Option<usize> = self
+            .header
+            .split_whitespace()
+            .nth(1)
+            .and_then(|h| h.parse().ok());
+        for code in 32u8..=126 {
+            if !self.has_char(code as char) {
+                out.push(Diagnostic::new(
+                    Issue::MissingChar,
+                    Some(code as char),
+                    None,
+                ));
+            }
+        }
+        for (ch, glyph) in self.iter_glyphs() {
+            let idx = ch as usize;
+            let offset = match &self.lazy {
+                Some(lazy) if self.glyphs_overlay[idx].is_none() => lazy
+                    .glyph_lines
+                    .get(lazy.glyph_line_start[idx] as usize)
+                    .map(|r| r.start),
+                _ => None,
+            };
+            let first = out.len();
+            if let Some(height) = height.filter(|_| offset.is_some()) {
+                if glyph.height != height {
+                    out.push(Diagnostic::new(
+                        Issue::HeightMismatch {
+                            declared: height,
+                            actual: glyph.height,
+                        },
+                        Some(ch),
+                        None,
+                    ));
+                }
+            }
+            check_glyph(glyph, ch, self.format == FigletFormat::Tlf, &mut out);
+            for d in &mut out[first..] {
+                d.offset = offset;
+            }
+        }
+        out
+    }
+
     /// Serialize this FIGlet font to bytes in .flf format.
+    ///
+    /// TOIlet fonts and fonts with colored glyphs are written as `.tlf` fonts, colors as ANSI
+    /// escapes in the glyph lines.
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
         let mut out = Vec::new();
 
         // Determine max height from all glyphs
         let max_height = self.compute_max_height();
+        let baseline = self.baseline.unwrap_or(max_height).clamp(1, max_height);
+        let signature = if self.format == FigletFormat::Tlf || self.is_colored() {
+            "tlf2a"
+        } else {
+            "flf2a"
+        };
 
         // Write header line
         // Format: flf2a<hardblank> height baseline maxlen smush comment_count
         let comment_count = self.comments.len();
         let header = format!(
-            "flf2a{} {} {} {} -1 {}\n",
-            self.hard_blank, max_height, max_height, 80, comment_count
+            "{}{} {} {} {} -1 {}\n",
+            signature, self.hard_blank, max_height, baseline, 80, comment_count
         );
         out.extend(header.as_bytes());
 
@@ -347,6 +518,15 @@ impl FigletFont {
         Ok(out)
     }
 
+    /// Whether any glyph carries colors (colored TOIlet fonts).
+    pub fn is_colored(&self) -> bool {
+        self.iter_glyphs().any(|(_, g)| {
+            g.parts
+                .iter()
+                .any(|p| matches!(p, GlyphPart::AnsiChar { .. }))
+        })
+    }
+
     fn compute_max_height(&self) -> usize {
         let mut max_h = 1usize;
         for ch in 32u8..=127u8 {
@@ -362,13 +542,31 @@ impl FigletFont {
             // Build lines from glyph parts
             let mut lines: Vec<String> = Vec::new();
             let mut current_line = String::new();
+            let mut colored = false;
 
             for part in &glyph.parts {
+                if colored && !matches!(part, GlyphPart::AnsiChar { .. }) {
+                    current_line.push_str("\x1B[0m");
+                    colored = false;
+                }
                 match part {
                     GlyphPart::NewLine => {
                         lines.push(current_line);
                         current_line = String::new();
                     }
+                    GlyphPart::AnsiChar { ch, fg, bg, blink } => {
+                        // SGR colors follow ANSI order, not DOS order.
+                        const ANSI: [u8; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
+                        current_line.push_str(&format!(
+                            "\x1B[0;{}{}{};{}m",
+                            if fg & 0x08 != 0 { "1;" } else { "" },
+                            if *blink { "5;" } else { "" },
+                            30 + ANSI[(fg & 0x07) as usize],
+                            40 + ANSI[(bg & 0x07) as usize]
+                        ));
+                        current_line.push(*ch);
+                        colored = true;
+                    }
                     GlyphPart::HardBlank => {
                         current_line.push(self.hard_blank);
                     }
@@ -381,6 +579,9 @@ impl FigletFont {
                     }
                 }
             }
+            if colored {
+                current_line.push_str("\x1B[0m");
+            }
             // Don't forget the last line if not empty
             if !current_line.is_empty() || lines.is_empty() {
                 lines.push(current_line);
@@ -443,24 +644,27 @@ fn read_character_ranges(
     line_idx: &mut usize,
     height: usize,
     bytes: &[u8],
+    ch: char,
 ) -> Result<Vec<Range<usize>>> {
     let mut out = Vec::with_capacity(height);
     for _ in 0..height {
+        // Line numbers are 1-based.
+        let line = *line_idx + 1;
         let r = lines
             .get(*line_idx)
-            .ok_or(FontError::FigletIncompleteChar)?
+            .ok_or(FontError::FigletIncompleteChar { line, ch })?
             .clone();
         *line_idx += 1;
-        let line = &bytes[r.clone()];
-        if line.ends_with(b"@@") {
+        let text = &bytes[r.clone()];
+        if text.ends_with(b"@@") {
             out.push(r.start..(r.end - 2));
             break;
         }
-        if line.ends_with(b"@") {
+        if text.ends_with(b"@") {
             out.push(r.start..(r.end - 1));
             continue;
         }
-        return Err(FontError::FigletMissingMarker);
+        return Err(FontError::FigletMissingMarker { line, ch });
     }
     Ok(out)
 }
@@ -478,9 +682,28 @@ fn decode_glyph(lazy: &LazyFigletSource, idx: usize) -> Glyph {
         let r = &lazy.glyph_lines[start + row];
         let s = unsafe { std::str::from_utf8_unchecked(&lazy.bytes[r.clone()]) };
         let mut line_width = 0usize;
-        for ch in s.chars() {
+        // Colored TOIlet fonts embed ANSI SGR escapes.
+        let mut color: Option<SgrColor> = None;
+        let mut chars = s.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\x1B' {
+                let params: String = chars
+                    .by_ref()
+                    .skip_while(|c| *c == '[')
+                    .take_while(|c| *c != 'm')
+                    .collect();
+                color = SgrColor::apply(color, &params);
+                continue;
+            }
             if ch == lazy.hard_blank {
                 parts.push(GlyphPart::HardBlank);
+            } else if let Some(c) = color {
+                parts.push(GlyphPart::AnsiChar {
+                    ch,
+                    fg: c.fg,
+                    bg: c.bg,
+                    blink: c.blink,
+                });
             } else {
                 parts.push(GlyphPart::Char(ch));
             }
@@ -495,3 +718,45 @@ fn decode_glyph(lazy: &LazyFigletSource, idx: usize) -> Glyph {
         parts,
     }
 }
+
+/// Color state while decoding ANSI escapes; `None` means uncolored.
+#[derive(Copy, Clone)]
+struct SgrColor {
+    fg: u8,
+    bg: u8,
+    blink: bool,
+}
+
+impl SgrColor {
+    fn apply(color: Option<Self>, params: &str) -> Option<Self> {
+        // SGR colors follow ANSI order, DOS indices swap red and blue.
+        const DOS: [u8; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
+        let mut c = color.unwrap_or(SgrColor {
+            fg: 7,
+            bg: 0,
+            blink: false,
+        });
+        let mut colored = color.is_some();
+        for p in params.split(';').map(|p| p.parse::<u8>().unwrap_or(0)) {
+            match p {
+                0 => {
+                    c = SgrColor {
+                        fg: 7,
+                        bg: 0,
+                        blink: false,
+                    };
+                    colored = false;
+                    continue;
+                }
+                1 => c.fg |= 0x08,
+                5 => c.blink = true,
+                30..=37 => c.fg = (c.fg & 0x08) | DOS[(p - 30) as usize],
+                40..=47 => c.bg = DOS[(p - 40) as usize],
+                90..=97 => c.fg = 0x08 | DOS[(p - 90) as usize],
+                _ => continue,
+            }
+            colored = true;
+        }
+        colored.then_some(c)
+    }
+}
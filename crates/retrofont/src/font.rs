@@ -1,8 +1,13 @@
 use std::io::{Read, Write};
 use std::sync::Arc;
+use std::{fs, path::Path};
 
 use crate::{
-    figlet::FigletFont, glyph::RenderOptions, tdf::TdfFont, FontError, FontTarget, Result,
+    figlet::{FigletFont, FigletFormat},
+    glyph::{Glyph, RenderOptions},
+    metrics::FontMetrics,
+    tdf::TdfFont,
+    FontError, FontTarget, Result,
 };
 
 /// Unified font enum encapsulating all supported font kinds.
@@ -10,6 +15,7 @@ use crate::{
 /// Replaces the trait-based dynamic dispatch with a simple tagged union. This keeps
 /// font operations ergonomic without requiring generics or trait objects when only
 /// supporting built-in formats.
+#[allow(clippy::large_enum_variant)]
 pub enum Font {
     Figlet(FigletFont),
     Tdf(TdfFont),
@@ -45,6 +51,19 @@ impl Font {
         }
     }
 
+    /// The glyph for `ch`, without the case fallback of [`Font::resolve_char`].
+    pub fn glyph(&self, ch: char) -> Option<&Glyph> {
+        match self {
+            Font::Figlet(f) => f.glyph(ch),
+            Font::Tdf(f) => f.glyph(ch),
+        }
+    }
+
+    /// Baseline, cap height, x-height and other metrics, see [`FontMetrics`].
+    pub fn metrics(&self) -> FontMetrics {
+        FontMetrics::of(self)
+    }
+
     /// Returns the maximum height of all glyphs in the font.
     pub fn max_height(&self) -> usize {
         match self {
@@ -61,28 +80,10 @@ impl Font {
         }
     }
 
-    pub fn render_glyph<T: FontTarget>(
-        &self,
-        target: &mut T,
-        ch: char,
-        options: &RenderOptions,
-    ) -> Result<()> {
-        // Special handling for space character if not defined in font
-        if ch == ' ' && !self.has_char(' ') {
-            // Calculate reasonable space width: use average glyph width or default to 1
-            let space_width = self.spacing().unwrap_or(1);
-
-            // Render empty space by drawing spaces for the calculated width
-            for _ in 0..space_width {
-                target
-                    .draw(crate::Cell::new(' ', None, None, false))
-                    .map_err(|_| FontError::InvalidGlyph)?;
-            }
-            return Ok(());
-        }
-
-        // Try to find the character or its case variant
-        let char_to_render = if self.has_char(ch) {
+    /// The character drawn for `ch`: `ch` itself, or its other case if only that one is in
+    /// the font.
+    pub fn resolve_char(&self, ch: char) -> char {
+        if self.has_char(ch) {
             ch
         } else if ch.is_alphabetic() {
             // Try the opposite case if the original character is not found
@@ -103,13 +104,52 @@ impl Font {
             }
         } else {
             ch
-        };
+        }
+    }
 
-        let glyph = match self {
-            Font::Figlet(f) => f.glyph(char_to_render),
-            Font::Tdf(f) => f.glyph(char_to_render),
-        };
-        let Some(glyph) = glyph else {
+    /// Rows from the top of a line to the baseline: the font's `baseline` if set, else the
+    /// glyph height for FIGlet fonts and [`TdfFont::detect_baseline`] for TDF fonts.
+    pub fn baseline(&self) -> usize {
+        match self {
+            Font::Figlet(f) => f.baseline.unwrap_or_else(|| self.max_height()),
+            Font::Tdf(f) => f.baseline.unwrap_or_else(|| f.detect_baseline()),
+        }
+    }
+
+    /// Rows of the glyph for `ch` below the baseline, see [`TdfFont::descent`].
+    pub fn descent(&self, ch: char) -> usize {
+        let ch = self.resolve_char(ch);
+        match self {
+            Font::Figlet(f) => f
+                .glyph(ch)
+                .map_or(0, |g| g.height.saturating_sub(self.baseline())),
+            Font::Tdf(f) => f.descent(ch),
+        }
+    }
+
+    pub fn render_glyph<T: FontTarget>(
+        &self,
+        target: &mut T,
+        ch: char,
+        options: &RenderOptions,
+    ) -> Result<()> {
+        // Special handling for space character if not defined in font
+        if ch == ' ' && !self.has_char(' ') {
+            // Calculate reasonable space width: use average glyph width or default to 1
+            let space_width = self.spacing().unwrap_or(1);
+
+            // Render empty space by drawing spaces for the calculated width
+            for _ in 0..space_width {
+                target
+                    .draw(crate::Cell::new(' ', None, None, false))
+                    .map_err(|_| FontError::InvalidGlyph)?;
+            }
+            return Ok(());
+        }
+
+        let char_to_render = self.resolve_char(ch);
+
+        let Some(glyph) = self.glyph(char_to_render) else {
             return Err(FontError::UnknownChar(ch));
         };
         glyph.render(target, options)
@@ -122,8 +162,8 @@ impl Font {
     /// - Multiple fonts for TDF bundles (which can contain many fonts)
     /// - An error if the format is unrecognized or parsing fails
     pub fn load(bytes: &[u8]) -> Result<Vec<Font>> {
-        // Attempt FIGlet: header starts with 'flf2a'
-        if bytes.len() >= 5 && &bytes[0..5] == b"flf2a" {
+        // Attempt FIGlet: header starts with 'flf2a' (or 'tlf2a' for TOIlet)
+        if bytes.len() >= 5 && (&bytes[0..5] == b"flf2a" || &bytes[0..5] == b"tlf2a") {
             let fig = FigletFont::load(bytes)?;
             return Ok(vec![Font::Figlet(fig)]);
         }
@@ -138,6 +178,14 @@ impl Font {
         Err(FontError::UnrecognizedFormat)
     }
 
+    /// Load fonts from a file; errors name the file, see [`FontError::File`].
+    pub fn load_file(path: &Path) -> Result<Vec<Font>> {
+        fs::read(path)
+            .map_err(FontError::from)
+            .and_then(Self::load_owned)
+            .map_err(|e| e.in_file(path))
+    }
+
     /// Load fonts from an owned buffer without copying.
     pub fn load_owned(bytes: Vec<u8>) -> Result<Vec<Font>> {
         let arc: Arc<[u8]> = Arc::<[u8]>::from(bytes);
@@ -148,8 +196,8 @@ impl Font {
     pub fn load_arc(bytes: Arc<[u8]>) -> Result<Vec<Font>> {
         let b = bytes.as_ref();
 
-        // Attempt FIGlet: header starts with 'flf2a'
-        if b.len() >= 5 && &b[0..5] == b"flf2a" {
+        // Attempt FIGlet: header starts with 'flf2a' (or 'tlf2a' for TOIlet)
+        if b.len() >= 5 && (&b[0..5] == b"flf2a" || &b[0..5] == b"tlf2a") {
             let fig = FigletFont::load_arc(bytes)?;
             return Ok(vec![Font::Figlet(fig)]);
         }
@@ -194,10 +242,11 @@ impl Font {
     /// Returns the default file extension for this font type.
     ///
     /// - TDF fonts: `"tdf"`
-    /// - FIGlet fonts: `"flf"`
+    /// - FIGlet fonts: `"flf"`, or `"tlf"` for TOIlet fonts and fonts with colors
     pub fn default_extension(&self) -> &'static str {
         match self {
             Font::Tdf(_) => "tdf",
+            Font::Figlet(f) if f.format == FigletFormat::Tlf || f.is_colored() => "tlf",
             Font::Figlet(_) => "flf",
         }
     }
@@ -5,11 +5,23 @@ use crate::{
 // Use CP437 to Unicode mapping from TDF module for consistent Unicode output
 use crate::tdf::CP437_TO_UNICODE;
 
+/// Options for rendering glyphs.
+///
+/// Build them from [`RenderOptions::default`] (or [`RenderOptions::edit`]) and the builder
+/// methods; new options may be added in later versions.
 #[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct RenderOptions {
     pub render_mode: RenderMode,
     pub outline_style: usize,
+    /// Interpret the attribute blink bit as a high-intensity background (iCE colors).
+    /// Color cells then carry background colors 0-15 and never blink.
+    pub ice_colors: bool,
+    /// Color index remapping applied to color cells: index `i` is drawn as `remap[i]`.
+    pub palette_remap: Option<[u8; 16]>,
+    /// How [`crate::Canvas::render_text`] places glyphs of different heights.
+    pub align: VerticalAlign,
 }
 
 impl RenderOptions {
@@ -20,7 +32,42 @@ impl RenderOptions {
     pub fn edit() -> Self {
         Self {
             render_mode: RenderMode::Edit,
-            outline_style: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Set [`RenderOptions::outline_style`] (0-18).
+    pub fn outline_style(mut self, style: usize) -> Self {
+        self.outline_style = style;
+        self
+    }
+
+    /// Set [`RenderOptions::ice_colors`].
+    pub fn ice_colors(mut self, ice_colors: bool) -> Self {
+        self.ice_colors = ice_colors;
+        self
+    }
+
+    /// Set [`RenderOptions::align`].
+    pub fn align(mut self, align: VerticalAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Draw color index `from` as `to`, e.g. `remap_color(4, 2)` turns a red font green.
+    pub fn remap_color(mut self, from: u8, to: u8) -> Self {
+        let table = self
+            .palette_remap
+            .get_or_insert(std::array::from_fn(|i| i as u8));
+        table[from as usize & 0x0F] = to & 0x0F;
+        self
+    }
+
+    /// Color index after applying [`RenderOptions::palette_remap`].
+    pub fn map_color(&self, index: u8) -> u8 {
+        match &self.palette_remap {
+            Some(table) => table[index as usize & 0x0F],
+            None => index,
         }
     }
 }
@@ -33,6 +80,29 @@ pub enum RenderMode {
     Edit,
 }
 
+/// Vertical placement of glyphs in a line of text.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerticalAlign {
+    /// Glyph tops on the first row, as TheDraw places them.
+    #[default]
+    Top,
+    /// Glyph bottoms on the font's baseline, with descenders below it. See
+    /// [`crate::Font::baseline`].
+    Baseline,
+}
+
+impl VerticalAlign {
+    /// Parse an alignment name as used on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "top" => Some(VerticalAlign::Top),
+            "baseline" => Some(VerticalAlign::Baseline),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GlyphPart {
@@ -53,7 +123,8 @@ pub enum GlyphPart {
     Skip,
     /// Plain Unicode character cell (always a visible character, never a space)
     Char(char),
-    /// Color font cell with per-cell attributes (foreground/background 0-15)
+    /// Color font cell with per-cell attributes (foreground/background 0-15).
+    /// Attributed spaces and hard blanks (`ch` = NBSP) are visible background cells.
     AnsiChar {
         ch: char,
         fg: u8,
@@ -62,6 +133,17 @@ pub enum GlyphPart {
     },
 }
 
+impl GlyphPart {
+    /// Whether the cell shows nothing: transparent cells, spaces and spaces on black. Hard
+    /// blanks and outline markers count as ink.
+    pub fn is_blank(&self) -> bool {
+        matches!(
+            self,
+            GlyphPart::Char(' ') | GlyphPart::Skip | GlyphPart::AnsiChar { ch: ' ', bg: 0, .. }
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Glyph {
@@ -216,9 +298,7 @@ impl Glyph {
                         .map_err(|_| FontError::InvalidGlyph)?;
                 }
                 GlyphPart::Skip => {
-                    target
-                        .skip()
-                        .map_err(|_| FontError::InvalidGlyph)?;
+                    target.skip().map_err(|_| FontError::InvalidGlyph)?;
                 }
                 GlyphPart::Char(c) => {
                     target
@@ -226,8 +306,22 @@ impl Glyph {
                         .map_err(|_| FontError::InvalidGlyph)?;
                 }
                 GlyphPart::AnsiChar { ch, fg, bg, blink } => {
+                    // Attributed hard blanks show as plain spaces outside Edit mode.
+                    let ch = if *ch == CP437_TO_UNICODE[0xFF]
+                        && options.render_mode != RenderMode::Edit
+                    {
+                        ' '
+                    } else {
+                        *ch
+                    };
+                    let (bg, blink) = if options.ice_colors {
+                        (*bg | if *blink { 0x08 } else { 0x00 }, false)
+                    } else {
+                        (*bg, *blink)
+                    };
+                    let (fg, bg) = (options.map_color(*fg), options.map_color(bg));
                     target
-                        .draw(Cell::new(*ch, Some(*fg), Some(*bg), *blink))
+                        .draw(Cell::new(ch, Some(fg), Some(bg), blink))
                         .map_err(|_| FontError::InvalidGlyph)?;
                 }
             }
@@ -0,0 +1,229 @@
+//! Editing glyphs cell by cell.
+//!
+//! [`Glyph`] stores its cells as a flat list of parts with [`GlyphPart::NewLine`] between
+//! rows. [`GlyphGrid`] turns that into rows of equal width that can be edited by position and
+//! turned back into a glyph with its size recomputed.
+use crate::{
+    glyph::{Glyph, GlyphPart},
+    metrics::InkBounds,
+};
+
+/// A glyph as a rectangle of cells.
+///
+/// Short rows are padded with transparent [`GlyphPart::Skip`] cells. End markers (`&` in TDF
+/// outline fonts) take no cell; the grid remembers their column and writes them back.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GlyphGrid {
+    width: usize,
+    rows: Vec<Vec<GlyphPart>>,
+    // Column of the end marker in each row
+    end_markers: Vec<Option<usize>>,
+}
+
+impl GlyphGrid {
+    /// A grid of transparent cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            rows: vec![vec![GlyphPart::Skip; width]; height],
+            end_markers: vec![None; height],
+        }
+    }
+
+    pub fn from_glyph(glyph: &Glyph) -> Self {
+        let mut rows = vec![Vec::new()];
+        let mut end_markers = vec![None];
+        for part in &glyph.parts {
+            match part {
+                GlyphPart::NewLine => {
+                    rows.push(Vec::new());
+                    end_markers.push(None);
+                }
+                GlyphPart::EndMarker => {
+                    *end_markers.last_mut().unwrap() = Some(rows.last().unwrap().len());
+                }
+                part => rows.last_mut().unwrap().push(part.clone()),
+            }
+        }
+        if glyph.parts.is_empty() {
+            rows.clear();
+            end_markers.clear();
+        }
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in &mut rows {
+            row.resize(width, GlyphPart::Skip);
+        }
+        Self {
+            width,
+            rows,
+            end_markers,
+        }
+    }
+
+    /// The glyph with `width` and `height` taken from the grid.
+    pub fn to_glyph(&self) -> Glyph {
+        let mut parts = Vec::with_capacity((self.width + 1) * self.rows.len());
+        for (y, row) in self.rows.iter().enumerate() {
+            if y > 0 {
+                parts.push(GlyphPart::NewLine);
+            }
+            for (x, cell) in row.iter().enumerate() {
+                if self.end_markers[y] == Some(x) {
+                    parts.push(GlyphPart::EndMarker);
+                }
+                parts.push(cell.clone());
+            }
+            if self.end_markers[y].is_some_and(|x| x >= row.len()) {
+                parts.push(GlyphPart::EndMarker);
+            }
+        }
+        Glyph {
+            width: self.width,
+            height: self.rows.len(),
+            parts,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn rows(&self) -> &[Vec<GlyphPart>] {
+        &self.rows
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&GlyphPart> {
+        self.rows.get(y)?.get(x)
+    }
+
+    /// Set the cell at (`x`, `y`), growing the grid with transparent cells if it lies outside.
+    pub fn set(&mut self, x: usize, y: usize, part: GlyphPart) {
+        if x >= self.width || y >= self.rows.len() {
+            self.resize(self.width.max(x + 1), self.rows.len().max(y + 1));
+        }
+        self.rows[y][x] = part;
+    }
+
+    /// Insert a transparent row before row `y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y > height`.
+    pub fn insert_row(&mut self, y: usize) {
+        self.rows.insert(y, vec![GlyphPart::Skip; self.width]);
+        self.end_markers.insert(y, None);
+    }
+
+    /// Remove row `y`, returning its cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y >= height`.
+    pub fn delete_row(&mut self, y: usize) -> Vec<GlyphPart> {
+        self.end_markers.remove(y);
+        self.rows.remove(y)
+    }
+
+    /// Insert a transparent column before column `x`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x > width`.
+    pub fn insert_column(&mut self, x: usize) {
+        assert!(x <= self.width, "column {x} out of range");
+        for row in &mut self.rows {
+            row.insert(x, GlyphPart::Skip);
+        }
+        for marker in self.end_markers.iter_mut().flatten() {
+            if *marker >= x {
+                *marker += 1;
+            }
+        }
+        self.width += 1;
+    }
+
+    /// Remove column `x`, returning its cells from top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x >= width`.
+    pub fn delete_column(&mut self, x: usize) -> Vec<GlyphPart> {
+        assert!(x < self.width, "column {x} out of range");
+        for marker in self.end_markers.iter_mut().flatten() {
+            if *marker > x {
+                *marker -= 1;
+            }
+        }
+        self.width -= 1;
+        self.rows.iter_mut().map(|row| row.remove(x)).collect()
+    }
+
+    /// Change the size, cutting cells off at the right and bottom or adding transparent ones.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.rows.resize(height, vec![GlyphPart::Skip; self.width]);
+        self.end_markers.resize(height, None);
+        for row in &mut self.rows {
+            row.resize(width, GlyphPart::Skip);
+        }
+        for marker in &mut self.end_markers {
+            *marker = marker.map(|x| x.min(width));
+        }
+        self.width = width;
+    }
+
+    /// Bounds of the cells that are not blank (see [`GlyphPart::is_blank`]); `None` without
+    /// ink.
+    pub fn ink_bounds(&self) -> Option<InkBounds> {
+        let ink = |row: &Vec<GlyphPart>| row.iter().any(|p| !p.is_blank());
+        let top = self.rows.iter().position(ink)?;
+        let bottom = self.rows.iter().rposition(ink).unwrap_or(top);
+        let column_ink = |x: usize| self.rows.iter().any(|row| !row[x].is_blank());
+        let left = (0..self.width).find(|&x| column_ink(x)).unwrap_or(0);
+        let right = (0..self.width).rfind(|&x| column_ink(x)).unwrap_or(left);
+        Some(InkBounds {
+            x: left,
+            y: top,
+            width: right + 1 - left,
+            height: bottom + 1 - top,
+        })
+    }
+
+    /// Remove blank rows and columns around the ink (see [`GlyphPart::is_blank`]), returning
+    /// how many columns and rows were cut off at the left and top. A glyph without ink
+    /// becomes empty.
+    pub fn crop_to_ink(&mut self) -> (usize, usize) {
+        let Some(ink) = self.ink_bounds() else {
+            *self = Self::default();
+            return (0, 0);
+        };
+        self.rows.truncate(ink.bottom());
+        self.end_markers.truncate(ink.bottom());
+        self.rows.drain(..ink.y);
+        self.end_markers.drain(..ink.y);
+        for row in &mut self.rows {
+            row.truncate(ink.right());
+            row.drain(..ink.x);
+        }
+        for marker in &mut self.end_markers {
+            *marker = marker.map(|x| x.saturating_sub(ink.x).min(ink.width));
+        }
+        self.width = ink.width;
+        (ink.x, ink.y)
+    }
+}
+
+impl From<&Glyph> for GlyphGrid {
+    fn from(glyph: &Glyph) -> Self {
+        Self::from_glyph(glyph)
+    }
+}
+
+impl From<GlyphGrid> for Glyph {
+    fn from(grid: GlyphGrid) -> Self {
+        grid.to_glyph()
+    }
+}
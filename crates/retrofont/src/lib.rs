@@ -1,31 +1,85 @@
 //! retrofont: retro terminal font toolkit.
 //! Features: TDF parsing/rendering, FIGlet placeholder, conversion stubs.
 
+pub mod bundle;
+pub mod canvas;
+pub mod colorize;
 pub mod convert;
+pub mod cp437;
+pub mod effects;
 mod error;
 pub mod figlet;
 mod font;
 mod glyph;
+pub mod grid;
+pub mod metrics;
+pub mod output;
+pub mod palette;
+pub mod stack;
 pub use glyph::{transform_outline, OUTLINE_CHAR_SET_UNICODE};
 pub mod tdf;
+pub mod transform;
+pub mod validate;
+pub use canvas::Canvas;
 pub use error::{FontError, Result};
 pub use font::Font;
-pub use glyph::{Glyph, GlyphPart, RenderMode, RenderOptions};
+pub use glyph::{Glyph, GlyphPart, RenderMode, RenderOptions, VerticalAlign};
+pub use grid::GlyphGrid;
+pub use metrics::FontMetrics;
+pub use palette::{Color, Palette, Rgb};
+pub use stack::FontStack;
 
 // Test utilities
 pub mod test_support;
 
+/// One rendered character cell. Create cells with [`Cell::new`] or [`Cell::from_attribute`];
+/// new fields may be added in later versions.
 #[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
 pub struct Cell {
     pub ch: char,
+    /// Foreground color (0-15)
     pub fg: Option<u8>,
+    /// Background color (0-7, or 0-15 when rendered with iCE colors)
     pub bg: Option<u8>,
     pub blink: bool,
+    /// Exact foreground color for true color output; `fg` holds the nearest palette index.
+    pub fg_rgb: Option<Rgb>,
+    /// Exact background color for true color output; `bg` holds the nearest palette index.
+    pub bg_rgb: Option<Rgb>,
 }
 
 impl Cell {
     pub fn new(ch: char, fg: Option<u8>, bg: Option<u8>, blink: bool) -> Self {
-        Self { ch, fg, bg, blink }
+        Self {
+            ch,
+            fg,
+            bg,
+            blink,
+            fg_rgb: None,
+            bg_rgb: None,
+        }
+    }
+
+    /// Create a cell from a DOS text mode attribute byte.
+    ///
+    /// Bit 7 selects blink, or a high-intensity background (8-15) in iCE mode.
+    pub fn from_attribute(ch: char, attr: u8, ice_colors: bool) -> Self {
+        let fg = attr & 0x0F;
+        if ice_colors {
+            Self::new(ch, Some(fg), Some(attr >> 4), false)
+        } else {
+            Self::new(ch, Some(fg), Some((attr >> 4) & 0x07), attr & 0x80 != 0)
+        }
+    }
+
+    /// DOS text mode attribute byte of this cell (light gray on black for missing colors).
+    ///
+    /// A high-intensity background and blink both map to bit 7.
+    pub fn attribute(&self) -> u8 {
+        let fg = self.fg.unwrap_or(7) & 0x0F;
+        let bg = self.bg.unwrap_or(0) & 0x0F;
+        (bg << 4) | fg | if self.blink { 0x80 } else { 0x00 }
     }
 }
 
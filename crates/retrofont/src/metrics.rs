@@ -0,0 +1,116 @@
+//! Font geometry: baseline, cap height, x-height, descenders and advances.
+//!
+//! Glyph sizes alone say little about the letter shapes: FIGlet glyphs all share one height
+//! with blank rows above and below the ink, and TDF glyphs carry transparent cells. The
+//! metrics here are measured on the ink of the glyphs (cells that are not blank, see
+//! [`crate::GlyphPart::is_blank`]) relative to the baseline from [`Font::baseline`].
+use crate::{glyph::Glyph, grid::GlyphGrid, Font};
+
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// Lowercase letters without ascenders or descenders.
+const X_HEIGHT_CHARS: &str = "acemnorsuvwxz";
+const DESCENDER_CHARS: &str = "gjpqy";
+
+/// The smallest rectangle around the ink of a glyph, in cells from its top-left corner.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InkBounds {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl InkBounds {
+    /// Ink bounds of `glyph`; `None` if it has no ink.
+    pub fn of(glyph: &Glyph) -> Option<Self> {
+        GlyphGrid::from_glyph(glyph).ink_bounds()
+    }
+
+    /// First column right of the ink.
+    pub fn right(&self) -> usize {
+        self.x + self.width
+    }
+
+    /// First row below the ink.
+    pub fn bottom(&self) -> usize {
+        self.y + self.height
+    }
+}
+
+/// Vertical and horizontal metrics of a font, in cells.
+///
+/// Heights are measured from the baseline up, the descender depth from the baseline down.
+/// Letters the font does not define are skipped; a metric without any letter to measure is 0.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontMetrics {
+    /// Height of the tallest glyph.
+    pub height: usize,
+    /// Rows from the top of a line to the baseline.
+    pub baseline: usize,
+    /// Most common ink height of the uppercase letters above the baseline.
+    pub cap_height: usize,
+    /// Most common ink height of the lowercase letters without ascenders (`x`, `o`, ...).
+    pub x_height: usize,
+    /// Deepest ink below the baseline of `g`, `j`, `p`, `q` and `y`.
+    pub descender: usize,
+    /// Average glyph width of the printable ASCII characters, which is how far the cursor
+    /// moves per character.
+    pub average_advance: f32,
+    pub max_advance: usize,
+}
+
+impl FontMetrics {
+    pub fn of(font: &Font) -> Self {
+        let baseline = font.baseline();
+        // Ink of `ch` relative to the baseline: rows above it and rows below it.
+        let ink = |ch: char| {
+            let glyph = font.glyph(ch)?;
+            let bounds = InkBounds::of(glyph)?;
+            let glyph_baseline = glyph.height.saturating_sub(font.descent(ch));
+            Some((
+                glyph_baseline.saturating_sub(bounds.y),
+                bounds.bottom().saturating_sub(glyph_baseline),
+            ))
+        };
+        let common_height = |chars: &str| {
+            most_common(chars.chars().filter_map(|ch| ink(ch).map(|(up, _)| up))).unwrap_or(0)
+        };
+
+        let widths: Vec<usize> = ('!'..='~')
+            .filter_map(|ch| font.glyph(ch))
+            .map(|g| g.width)
+            .collect();
+        let average_advance = if widths.is_empty() {
+            0.0
+        } else {
+            widths.iter().sum::<usize>() as f32 / widths.len() as f32
+        };
+        Self {
+            height: font.max_height(),
+            baseline,
+            cap_height: common_height(UPPERCASE),
+            x_height: common_height(X_HEIGHT_CHARS),
+            descender: DESCENDER_CHARS
+                .chars()
+                .filter_map(|ch| ink(ch).map(|(_, down)| down))
+                .max()
+                .unwrap_or(0),
+            average_advance,
+            max_advance: widths.iter().copied().max().unwrap_or(0),
+        }
+    }
+}
+
+/// Most frequent value, the larger one on ties.
+fn most_common(values: impl Iterator<Item = usize>) -> Option<usize> {
+    let mut counts = std::collections::BTreeMap::new();
+    for v in values {
+        *counts.entry(v).or_insert(0usize) += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(v, n)| (n, v))
+        .map(|(v, _)| v)
+}
@@ -0,0 +1,341 @@
+//! Output backends serializing a rendered [`Canvas`].
+//!
+//! Output is produced in two independent steps: a color backend ([`OutputFormat`]) turns the
+//! canvas into text with color codes, and an [`Encoding`] turns that text into bytes. ANSI
+//! output can therefore be written as CP437 for SyncTERM or DOS terminals, and BBS display
+//! files can be written as UTF-8 for modern boards.
+use crate::{
+    canvas::Canvas,
+    palette::{Palette, Rgb},
+    tdf::UNICODE_TO_CP437,
+    Cell,
+};
+
+/// Default DOS attribute: light gray on black.
+const DEFAULT_FG: u8 = 7;
+const DEFAULT_BG: u8 = 0;
+
+/// Color backend used to serialize a canvas.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OutputFormat {
+    /// ANSI escape sequences with 24-bit colors.
+    #[default]
+    Ansi,
+    /// BBS display file color codes.
+    Bbs(BbsDialect),
+}
+
+impl OutputFormat {
+    /// Parse a format name as used on the command line (`ansi` or a BBS dialect name).
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("ansi") {
+            return Some(OutputFormat::Ansi);
+        }
+        BbsDialect::from_name(name).map(OutputFormat::Bbs)
+    }
+
+    /// The encoding a format is usually written in: UTF-8 for ANSI, CP437 for BBS files.
+    pub fn default_encoding(self) -> Encoding {
+        match self {
+            OutputFormat::Ansi => Encoding::Utf8,
+            OutputFormat::Bbs(_) => Encoding::Cp437,
+        }
+    }
+}
+
+/// Color-code language used by a BBS display file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BbsDialect {
+    /// PCBoard / Wildcat `@Xbf` codes (hex background and foreground nibble).
+    PcBoard,
+    /// Renegade / Mystic `|NN` pipe codes (`|00`-`|15` foreground, `|16`-`|23` background,
+    /// `|24`-`|31` blinking or iCE background).
+    Pipe,
+    /// Synchronet Ctrl-A codes (`^AE` for iCE backgrounds).
+    CtrlA,
+    /// Avatar/0 `^V^A<attr>` attribute sequences (`^V^B` for blink or iCE backgrounds).
+    Avatar,
+}
+
+impl BbsDialect {
+    /// Parse a dialect name as used on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "pcboard" | "pcb" | "wildcat" => Some(BbsDialect::PcBoard),
+            "pipe" | "renegade" | "mystic" => Some(BbsDialect::Pipe),
+            "ctrl-a" | "ctrla" | "synchronet" => Some(BbsDialect::CtrlA),
+            "avatar" | "avt" => Some(BbsDialect::Avatar),
+            _ => None,
+        }
+    }
+}
+
+/// Character encoding of the produced bytes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// IBM code page 437, as expected by DOS terminals and SyncTERM.
+    Cp437,
+}
+
+impl Encoding {
+    /// Parse an encoding name as used on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "utf8" | "utf-8" => Some(Encoding::Utf8),
+            "cp437" | "ibm437" | "dos" => Some(Encoding::Cp437),
+            _ => None,
+        }
+    }
+}
+
+/// Encoded output together with the characters that could not be represented.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EncodedOutput {
+    pub bytes: Vec<u8>,
+    /// Distinct characters that have no equivalent in the target encoding, in order of first
+    /// appearance. Each occurrence was written as `?`.
+    pub unencodable: Vec<char>,
+}
+
+/// Encode text into bytes.
+///
+/// Control characters below 0x20 (escape sequences, line breaks, BBS control codes) are
+/// passed through as raw bytes. For CP437, every other character is mapped through
+/// [`UNICODE_TO_CP437`]; characters without a mapping are written as `?` and reported.
+pub fn encode(text: &str, encoding: Encoding) -> EncodedOutput {
+    match encoding {
+        Encoding::Utf8 => EncodedOutput {
+            bytes: text.as_bytes().to_vec(),
+            unencodable: Vec::new(),
+        },
+        Encoding::Cp437 => {
+            let mut out = EncodedOutput {
+                bytes: Vec::with_capacity(text.len()),
+                unencodable: Vec::new(),
+            };
+            for ch in text.chars() {
+                if (ch as u32) < 0x20 {
+                    out.bytes.push(ch as u8);
+                } else if let Some(&b) = UNICODE_TO_CP437.get(&ch) {
+                    out.bytes.push(b);
+                } else {
+                    out.bytes.push(b'?');
+                    if !out.unencodable.contains(&ch) {
+                        out.unencodable.push(ch);
+                    }
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Settings for [`render_canvas`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    pub encoding: Encoding,
+    /// RGB values for the color indices in true color output. BBS dialects write color
+    /// indices and leave the palette to the terminal.
+    pub palette: Palette,
+}
+
+impl OutputOptions {
+    /// Options for a format in its usual encoding.
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            encoding: format.default_encoding(),
+            palette: Palette::default(),
+        }
+    }
+}
+
+/// Serialize a canvas with the given color backend, encoding and palette.
+pub fn render_canvas(canvas: &Canvas, options: &OutputOptions) -> EncodedOutput {
+    let text = match options.format {
+        OutputFormat::Ansi => to_ansi_string(canvas, &options.palette),
+        OutputFormat::Bbs(dialect) => to_bbs_string(canvas, dialect),
+    };
+    encode(&text, options.encoding)
+}
+
+/// Serialize a canvas into an ANSI colored String, using `palette` for the RGB values.
+///
+/// SGR codes are only emitted when the colors or blink change; a cell that drops a color or
+/// blink (including transparent cells) resets the attributes first. Lines that end with
+/// attributes set end with a reset.
+pub fn to_ansi_string(canvas: &Canvas, palette: &Palette) -> String {
+    let mut out = String::new();
+    for (li, line) in canvas.lines().iter().enumerate() {
+        if li > 0 {
+            out.push('\n');
+        }
+        let mut current = AnsiAttr::default();
+        for cell in line {
+            let attr = AnsiAttr::of(cell.as_ref(), palette);
+            if attr != current {
+                let dropped = (current.fg.is_some() && attr.fg.is_none())
+                    || (current.bg.is_some() && attr.bg.is_none())
+                    || (current.blink && !attr.blink);
+                if dropped {
+                    out.push_str("\x1B[0m");
+                    current = AnsiAttr::default();
+                }
+                if attr.blink && !current.blink {
+                    out.push_str("\x1B[5m");
+                }
+                if let Some(c) = attr.fg.filter(|c| current.fg != Some(*c)) {
+                    out.push_str(&format!("\x1B[38;2;{};{};{}m", c.r, c.g, c.b));
+                }
+                if let Some(c) = attr.bg.filter(|c| current.bg != Some(*c)) {
+                    out.push_str(&format!("\x1B[48;2;{};{};{}m", c.r, c.g, c.b));
+                }
+                current = attr;
+            }
+            out.push(cell.as_ref().map_or(' ', |c| c.ch));
+        }
+        if current != AnsiAttr::default() {
+            out.push_str("\x1B[0m");
+        }
+    }
+    out
+}
+
+/// Colors and blink of a cell in ANSI output; `None` leaves the terminal default.
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+struct AnsiAttr {
+    fg: Option<Rgb>,
+    bg: Option<Rgb>,
+    blink: bool,
+}
+
+impl AnsiAttr {
+    fn of(cell: Option<&Cell>, palette: &Palette) -> Self {
+        let Some(c) = cell else {
+            return Self::default();
+        };
+        // Exact RGB values win over palette indices
+        Self {
+            fg: c.fg_rgb.or(c.fg.map(|i| palette.rgb(i))),
+            bg: c.bg_rgb.or(c.bg.map(|i| palette.rgb(i))),
+            blink: c.blink,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct Attr {
+    fg: u8,
+    bg: u8,
+    blink: bool,
+}
+
+impl Attr {
+    fn of(cell: Option<&Cell>) -> Self {
+        match cell {
+            Some(c) => Attr {
+                fg: c.fg.unwrap_or(DEFAULT_FG) & 0x0F,
+                bg: c.bg.unwrap_or(DEFAULT_BG) & 0x0F,
+                blink: c.blink,
+            },
+            None => Attr::default_attr(),
+        }
+    }
+
+    fn default_attr() -> Self {
+        Attr {
+            fg: DEFAULT_FG,
+            bg: DEFAULT_BG,
+            blink: false,
+        }
+    }
+
+    /// DOS text mode attribute byte. Bit 7 is blink, or a bright background for iCE colors.
+    fn byte(self) -> u8 {
+        (self.bg << 4) | self.fg | if self.blink { 0x80 } else { 0x00 }
+    }
+}
+
+/// Serialize a canvas into a CP437 BBS display file in the given dialect.
+///
+/// Characters without a CP437 equivalent are written as `?`. Use [`render_canvas`] to pick
+/// a different encoding or to learn which characters could not be encoded.
+pub fn to_bbs_bytes(canvas: &Canvas, dialect: BbsDialect) -> Vec<u8> {
+    render_canvas(canvas, &OutputOptions::new(OutputFormat::Bbs(dialect))).bytes
+}
+
+/// Serialize a canvas into BBS display file text in the given dialect.
+///
+/// Color codes are only emitted when the attribute changes. Transparent cells and cells
+/// without colors use the DOS default attribute (light gray on black). Lines end with CR/LF.
+pub fn to_bbs_string(canvas: &Canvas, dialect: BbsDialect) -> String {
+    let mut out = String::new();
+    let mut current: Option<Attr> = None;
+    for (li, line) in canvas.lines().iter().enumerate() {
+        if li > 0 {
+            out.push_str("\r\n");
+        }
+        for cell in line {
+            let attr = Attr::of(cell.as_ref());
+            if current != Some(attr) {
+                write_attr(&mut out, dialect, attr);
+                current = Some(attr);
+            }
+            out.push(cell.as_ref().map_or(' ', |c| c.ch));
+        }
+    }
+    if current.is_some_and(|a| a != Attr::default_attr()) {
+        write_attr(&mut out, dialect, Attr::default_attr());
+    }
+    out.push_str("\r\n");
+    out
+}
+
+fn write_attr(out: &mut String, dialect: BbsDialect, attr: Attr) {
+    match dialect {
+        BbsDialect::PcBoard => {
+            out.push_str(&format!("@X{:02X}", attr.byte()));
+        }
+        BbsDialect::Pipe => {
+            // Mystic uses |24-|31 for blinking or, with iCE colors, bright backgrounds.
+            let bright = attr.blink || attr.bg & 0x08 != 0;
+            let bg = if bright { 24 } else { 16 } + (attr.bg & 0x07);
+            out.push_str(&format!("|{:02}|{:02}", attr.fg, bg));
+        }
+        BbsDialect::CtrlA => {
+            // Synchronet letters and digits follow ANSI color order, not DOS order.
+            const FG: [char; 8] = ['K', 'B', 'G', 'C', 'R', 'M', 'Y', 'W'];
+            const BG: [char; 8] = ['0', '4', '2', '6', '1', '5', '3', '7'];
+            out.push_str("\x01N");
+            if attr.fg & 0x08 != 0 {
+                out.push_str("\x01H");
+            }
+            out.push('\x01');
+            out.push(FG[(attr.fg & 0x07) as usize]);
+            out.push('\x01');
+            out.push(BG[(attr.bg & 0x07) as usize]);
+            if attr.bg & 0x08 != 0 {
+                out.push_str("\x01E");
+            }
+            if attr.blink {
+                out.push_str("\x01I");
+            }
+        }
+        BbsDialect::Avatar => {
+            // AVT/0 masks the attribute to 7 bits; bit 7 (blink or iCE background) is a
+            // separate ^V^B.
+            out.push_str("\x16\x01");
+            out.push((attr.byte() & 0x7F) as char);
+            if attr.byte() & 0x80 != 0 {
+                out.push_str("\x16\x02");
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! 16-color palettes used to turn color indices into RGB values.
+//!
+//! Color fonts store DOS color indices (0-15). A [`Palette`] decides which RGB color each
+//! index stands for when the output supports true color. The built-in palettes keep the DOS
+//! index order (0 black, 1 blue, 2 green, ... 15 white), mapping each index to the closest
+//! color of the emulated machine.
+use crate::{FontError, Result};
+
+/// An RGB color.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parse `#RRGGBB` or `RRGGBB`.
+    pub fn from_hex(s: &str) -> Option<Self> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        // from_str_radix alone would accept a leading '+'
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let v = u32::from_str_radix(hex, 16).ok()?;
+        Some(Self::new((v >> 16) as u8, (v >> 8) as u8, v as u8))
+    }
+}
+
+/// A color given either as a palette index or as an exact RGB value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    /// DOS color index (0-15).
+    Index(u8),
+    Rgb(Rgb),
+}
+
+impl Color {
+    /// DOS color names in index order, as accepted by [`Color::parse`].
+    pub const NAMES: [&'static str; 16] = [
+        "black",
+        "blue",
+        "green",
+        "cyan",
+        "red",
+        "magenta",
+        "brown",
+        "light-gray",
+        "dark-gray",
+        "light-blue",
+        "light-green",
+        "light-cyan",
+        "light-red",
+        "light-magenta",
+        "yellow",
+        "white",
+    ];
+
+    /// Parse a palette index (`0`-`15`), a DOS color name (`light-red`, `lightred` or
+    /// `light_red`; `grey` is accepted for `gray`) or `#RRGGBB`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Ok(i) = s.parse::<u8>() {
+            return (i < 16).then_some(Color::Index(i));
+        }
+        if s.starts_with('#') {
+            return Rgb::from_hex(s).map(Color::Rgb);
+        }
+        let name: String = s
+            .to_ascii_lowercase()
+            .replace("grey", "gray")
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .collect();
+        Self::NAMES
+            .iter()
+            .position(|n| n.replace('-', "") == name)
+            .map(|i| Color::Index(i as u8))
+    }
+}
+
+const fn rgb(v: u32) -> Rgb {
+    Rgb::new((v >> 16) as u8, (v >> 8) as u8, v as u8)
+}
+
+/// DOS default palette (VGA text mode colors)
+const DOS_COLORS: [Rgb; 16] = [
+    rgb(0x000000), // 0: black
+    rgb(0x0000AA), // 1: blue
+    rgb(0x00AA00), // 2: green
+    rgb(0x00AAAA), // 3: cyan
+    rgb(0xAA0000), // 4: red
+    rgb(0xAA00AA), // 5: magenta
+    rgb(0xAA5500), // 6: brown
+    rgb(0xAAAAAA), // 7: light gray
+    rgb(0x555555), // 8: dark gray
+    rgb(0x5555FF), // 9: light blue
+    rgb(0x55FF55), // 10: light green
+    rgb(0x55FFFF), // 11: light cyan
+    rgb(0xFF5555), // 12: light red
+    rgb(0xFF55FF), // 13: light magenta
+    rgb(0xFFFF55), // 14: yellow
+    rgb(0xFFFFFF), // 15: white
+];
+
+/// Color `value` (0-63) of the EGA's 64-color set: bits 0-2 are blue, green and red at
+/// two thirds intensity, bits 3-5 add one third.
+fn ega_rgb(value: u8) -> Rgb {
+    let level = |high: u8, low: u8| ((value >> high) & 1) * 0xAA + ((value >> low) & 1) * 0x55;
+    Rgb::new(level(2, 5), level(1, 4), level(0, 3))
+}
+
+/// IBM CGA monitors without the brown fix show color 6 as dark yellow.
+const CGA_COLORS: [Rgb; 16] = {
+    let mut c = DOS_COLORS;
+    c[6] = rgb(0xAAAA00);
+    c
+};
+
+/// Amiga Workbench 1.3 has four pens: blue, white, black and orange.
+const AMIGA_WORKBENCH_COLORS: [Rgb; 16] = [
+    rgb(0x000022), // 0: black
+    rgb(0x0055AA), // 1: blue
+    rgb(0x0055AA), // 2: green
+    rgb(0x0055AA), // 3: cyan
+    rgb(0xFF8800), // 4: red
+    rgb(0xFF8800), // 5: magenta
+    rgb(0xFF8800), // 6: brown
+    rgb(0xFFFFFF), // 7: light gray
+    rgb(0x0055AA), // 8: dark gray
+    rgb(0x0055AA), // 9: light blue
+    rgb(0xFFFFFF), // 10: light green
+    rgb(0xFFFFFF), // 11: light cyan
+    rgb(0xFF8800), // 12: light red
+    rgb(0xFF8800), // 13: light magenta
+    rgb(0xFF8800), // 14: yellow
+    rgb(0xFFFFFF), // 15: white
+];
+
+/// Commodore 64 colors (Pepto's measurements) in DOS index order.
+const C64_COLORS: [Rgb; 16] = [
+    rgb(0x000000), // 0: black
+    rgb(0x352879), // 1: blue
+    rgb(0x588D43), // 2: green
+    rgb(0x70A4B2), // 3: cyan
+    rgb(0x68372B), // 4: red
+    rgb(0x6F3D86), // 5: purple
+    rgb(0x433900), // 6: brown
+    rgb(0x959595), // 7: light grey
+    rgb(0x444444), // 8: dark grey
+    rgb(0x6C5EB5), // 9: light blue
+    rgb(0x9AD284), // 10: light green
+    rgb(0x6C6C6C), // 11: grey (no light cyan)
+    rgb(0x9A6759), // 12: light red
+    rgb(0x6F4F25), // 13: orange (no light magenta)
+    rgb(0xB8C76F), // 14: yellow
+    rgb(0xFFFFFF), // 15: white
+];
+
+/// A 16-color palette indexed by DOS color number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Palette {
+    pub name: String,
+    pub colors: [Rgb; 16],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::dos()
+    }
+}
+
+impl Palette {
+    pub fn new(name: impl Into<String>, colors: [Rgb; 16]) -> Self {
+        Self {
+            name: name.into(),
+            colors,
+        }
+    }
+
+    /// VGA text mode colors.
+    pub fn dos() -> Self {
+        Self::new("dos", DOS_COLORS)
+    }
+
+    /// Palette register values of the EGA at power-on; they select the DOS colors from the
+    /// 64-color set.
+    pub const EGA_DEFAULT_REGISTERS: [u8; 16] =
+        [0, 1, 2, 3, 4, 5, 20, 7, 56, 57, 58, 59, 60, 61, 62, 63];
+
+    /// The EGA palette with its default register values.
+    pub fn ega() -> Self {
+        Self::new("ega", Self::EGA_DEFAULT_REGISTERS.map(ega_rgb))
+    }
+
+    /// An EGA palette as programmed by software: each of the 16 palette registers selects one
+    /// color of the EGA's 64-color set (see [`Palette::ega_color`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FontError::InvalidPalette`] if a register value is above 63.
+    pub fn ega_registers(registers: [u8; 16]) -> Result<Self> {
+        if let Some(v) = registers.iter().find(|&&v| v > 63) {
+            return Err(FontError::InvalidPalette(format!(
+                "EGA register value {v} is out of range (0-63)"
+            )));
+        }
+        Ok(Self::new("ega", registers.map(ega_rgb)))
+    }
+
+    /// Color `value` (taken modulo 64) of the EGA's 64-color set.
+    pub fn ega_color(value: u8) -> Rgb {
+        ega_rgb(value % 64)
+    }
+
+    /// CGA colors with dark yellow instead of brown.
+    pub fn cga() -> Self {
+        Self::new("cga", CGA_COLORS)
+    }
+
+    /// The four Amiga Workbench 1.3 pens spread over the 16 indices.
+    pub fn amiga_workbench() -> Self {
+        Self::new("amiga", AMIGA_WORKBENCH_COLORS)
+    }
+
+    /// Commodore 64 colors.
+    pub fn c64() -> Self {
+        Self::new("c64", C64_COLORS)
+    }
+
+    /// Names accepted by [`Palette::by_name`].
+    pub const BUILTIN_NAMES: [&'static str; 5] = ["dos", "ega", "cga", "amiga", "c64"];
+
+    /// Look up a built-in palette by name.
+    ///
+    /// `ega:` followed by 16 comma-separated register values (0-63) gives a reprogrammed EGA
+    /// palette, see [`Palette::ega_registers`].
+    pub fn by_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if let Some(values) = name.strip_prefix("ega:") {
+            let registers: Vec<u8> = values
+                .split(',')
+                .map(|v| v.trim().parse().ok())
+                .collect::<Option<_>>()?;
+            return Self::ega_registers(registers.try_into().ok()?).ok();
+        }
+        match name.as_str() {
+            "dos" | "vga" => Some(Self::dos()),
+            "ega" => Some(Self::ega()),
+            "cga" => Some(Self::cga()),
+            "amiga" | "workbench" => Some(Self::amiga_workbench()),
+            "c64" => Some(Self::c64()),
+            _ => None,
+        }
+    }
+
+    /// Parse a user palette.
+    ///
+    /// Accepts one `#RRGGBB` value per line, or a GIMP palette (`.gpl`) with `R G B` lines.
+    /// Blank lines and `;`/`//` comments are ignored. The first 16 colors are used.
+    pub fn parse(name: impl Into<String>, text: &str) -> Result<Self> {
+        let mut colors = Vec::with_capacity(16);
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty()
+                || line.starts_with(';')
+                || line.starts_with("//")
+                || line.starts_with("GIMP Palette")
+                || line.starts_with("Name:")
+                || line.starts_with("Columns:")
+            {
+                continue;
+            }
+            if let Some(c) = Rgb::from_hex(line.split_whitespace().next().unwrap_or("")) {
+                colors.push(c);
+                continue;
+            }
+            if line.starts_with('#') {
+                // GIMP palette comment
+                continue;
+            }
+            let comps: Vec<u8> = line
+                .split_whitespace()
+                .take(3)
+                .map_while(|v| v.parse().ok())
+                .collect();
+            if comps.len() != 3 {
+                return Err(FontError::InvalidPalette(format!(
+                    "line {}: expected '#RRGGBB' or 'R G B', got '{}'",
+                    i + 1,
+                    line
+                )));
+            }
+            colors.push(Rgb::new(comps[0], comps[1], comps[2]));
+        }
+        if colors.len() < 16 {
+            return Err(FontError::InvalidPalette(format!(
+                "expected 16 colors, found {}",
+                colors.len()
+            )));
+        }
+        let mut table = [Rgb::default(); 16];
+        table.copy_from_slice(&colors[..16]);
+        Ok(Self::new(name, table))
+    }
+
+    /// Index of the palette color closest to `color`.
+    pub fn nearest(&self, color: Rgb) -> u8 {
+        self.nearest_of(color, 16)
+    }
+
+    /// Index of the color closest to `color` among the first `count` entries, e.g. 8 for
+    /// backgrounds without iCE colors.
+    pub fn nearest_of(&self, color: Rgb, count: usize) -> u8 {
+        let dist = |c: &Rgb| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(c.r, color.r) + d(c.g, color.g) + d(c.b, color.b)
+        };
+        self.colors
+            .iter()
+            .take(count.max(1))
+            .enumerate()
+            .min_by_key(|(_, c)| dist(c))
+            .map_or(0, |(i, _)| i as u8)
+    }
+
+    /// RGB value for a color index (taken modulo 16).
+    pub fn rgb(&self, index: u8) -> Rgb {
+        self.colors[index as usize % 16]
+    }
+}
@@ -0,0 +1,153 @@
+//! Rendering from a chain of fonts.
+//!
+//! Many fonts only define part of the character set: TDF fonts with capitals only, or
+//! without digits and punctuation. A [`FontStack`] takes each character from the first font
+//! that has it, so a primary font can borrow the missing glyphs from fallback fonts.
+use std::borrow::Cow;
+
+use crate::{
+    glyph::Glyph,
+    transform::{transform_glyph, Transform},
+    Cell, Font, FontError, FontTarget, RenderOptions, Result,
+};
+
+/// A primary font followed by fallback fonts.
+///
+/// Characters are looked up font by font, each with the case fallback of
+/// [`Font::resolve_char`], so lowercase letters of a capitals-only primary font still come
+/// from the primary font. Use [`crate::Canvas::render_stack`] to lay out text; with
+/// [`crate::VerticalAlign::Baseline`] glyphs from all fonts sit on the primary font's
+/// baseline.
+pub struct FontStack {
+    fonts: Vec<Font>,
+    // Factor each font's glyphs are scaled by when `scale_fallbacks` is set
+    scales: Vec<usize>,
+    /// Scale glyphs of fallback fonts up (2x or 4x) to roughly match the cap height of the
+    /// primary font.
+    pub scale_fallbacks: bool,
+}
+
+impl FontStack {
+    pub fn new(primary: Font) -> Self {
+        Self {
+            fonts: vec![primary],
+            scales: vec![1],
+            scale_fallbacks: false,
+        }
+    }
+
+    /// Add a fallback font after the ones already in the stack.
+    pub fn push(&mut self, font: Font) {
+        self.scales.push(scale_factor(&self.fonts[0], &font));
+        self.fonts.push(font);
+    }
+
+    /// Builder form of [`FontStack::push`].
+    pub fn with_fallback(mut self, font: Font) -> Self {
+        self.push(font);
+        self
+    }
+
+    /// The fonts, primary first.
+    pub fn fonts(&self) -> &[Font] {
+        &self.fonts
+    }
+
+    pub fn primary(&self) -> &Font {
+        &self.fonts[0]
+    }
+
+    /// Index of the font that renders `ch`, and the character it renders (see
+    /// [`Font::resolve_char`]).
+    pub fn font_for(&self, ch: char) -> Option<(usize, char)> {
+        self.fonts.iter().enumerate().find_map(|(i, font)| {
+            let resolved = font.resolve_char(ch);
+            font.has_char(resolved).then_some((i, resolved))
+        })
+    }
+
+    pub fn has_char(&self, ch: char) -> bool {
+        self.font_for(ch).is_some()
+    }
+
+    /// The glyph drawn for `ch`, scaled if it comes from a fallback font and
+    /// `scale_fallbacks` is set.
+    pub fn glyph(&self, ch: char) -> Option<Cow<'_, Glyph>> {
+        let (i, resolved) = self.font_for(ch)?;
+        let glyph = self.fonts[i].glyph(resolved)?;
+        let scale = self.scale(i);
+        if scale == 1 {
+            return Some(Cow::Borrowed(glyph));
+        }
+        let mut glyph = glyph.clone();
+        for _ in 0..scale.trailing_zeros() {
+            glyph = transform_glyph(&glyph, Transform::ScaleX2);
+            glyph = transform_glyph(&glyph, Transform::ScaleY2);
+        }
+        Some(Cow::Owned(glyph))
+    }
+
+    /// Rows from the top of a line to the baseline, taken from the primary font.
+    pub fn baseline(&self) -> usize {
+        self.primary().baseline()
+    }
+
+    /// Rows of the glyph for `ch` below the baseline, scaled with the glyph.
+    pub fn descent(&self, ch: char) -> usize {
+        self.font_for(ch).map_or(0, |(i, resolved)| {
+            self.fonts[i].descent(resolved) * self.scale(i)
+        })
+    }
+
+    /// Render the glyph for `ch` like [`Font::render_glyph`], from the first font that has
+    /// it. Spaces no font defines are as wide as the primary font's spacing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FontError::UnknownChar`] if no font in the stack has the character.
+    pub fn render_glyph<T: FontTarget>(
+        &self,
+        target: &mut T,
+        ch: char,
+        options: &RenderOptions,
+    ) -> Result<()> {
+        if ch == ' ' && !self.has_char(' ') {
+            for _ in 0..self.primary().spacing().unwrap_or(1) {
+                target
+                    .draw(Cell::new(' ', None, None, false))
+                    .map_err(|_| FontError::InvalidGlyph)?;
+            }
+            return Ok(());
+        }
+        let Some(glyph) = self.glyph(ch) else {
+            return Err(FontError::UnknownChar(ch));
+        };
+        glyph.render(target, options)
+    }
+
+    fn scale(&self, font: usize) -> usize {
+        if self.scale_fallbacks {
+            self.scales[font]
+        } else {
+            1
+        }
+    }
+}
+
+/// 1, 2 or 4: the power of two closest to the ratio of the cap heights.
+fn scale_factor(primary: &Font, fallback: &Font) -> usize {
+    let cap_height = |font: &Font| {
+        let metrics = font.metrics();
+        if metrics.cap_height > 0 {
+            metrics.cap_height
+        } else {
+            metrics.baseline
+        }
+    };
+    let ratio = cap_height(primary) as f32 / cap_height(fallback).max(1) as f32;
+    let mut scale = 1;
+    while scale < 4 && ratio >= scale as f32 * 1.5 {
+        scale *= 2;
+    }
+    scale
+}
@@ -1,11 +1,15 @@
 //! TDF font support (placeholder implementation)
 use crate::{
+    bundle::is_known_trailer,
+    cp437::{Cp437Encoder, Cp437Options},
     error::{FontError, Result},
     glyph::{Glyph, GlyphPart},
+    validate::{check_tdf_glyph, Diagnostic, Issue},
 };
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::sync::{Arc, OnceLock};
+use std::{fs, path::Path};
 
 // Constants adapted from icy_engine TheDrawFont
 const THE_DRAW_FONT_ID: &[u8; 18] = b"TheDraw FONTS file";
@@ -18,8 +22,35 @@ const TDF_FIRST_CHAR: u8 = b'!';
 const TDF_LAST_CHAR: u8 = b'~';
 
 pub const MAX_TDF_GLYPH_WIDTH: usize = 30;
+/// Longest font name (in bytes) a TDF file can store.
+pub const MAX_TDF_NAME_LEN: usize = FONT_NAME_LEN;
 pub const MAX_TDF_GLYPH_HEIGHT: usize = 12;
 const INVALID_GLYPH: u16 = 0xFFFF;
+// Attribute written for color font cells that carry no color of their own (light gray on black).
+const DEFAULT_ATTR: u8 = 0x07;
+// A black-on-black space in a color font is treated as a transparent cell.
+const TRANSPARENT_ATTR: u8 = 0x00;
+
+/// Serialized TDF data with the characters that had to be replaced, see
+/// [`TdfFont::to_bytes_with`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SavedTdf {
+    pub bytes: Vec<u8>,
+    /// Characters written as their nearest CP437 equivalent.
+    pub transliterated: Vec<char>,
+    /// Characters written as `?`.
+    pub unmappable: Vec<char>,
+}
+
+impl SavedTdf {
+    fn new(bytes: Vec<u8>, enc: Cp437Encoder) -> Self {
+        Self {
+            bytes,
+            transliterated: enc.transliterated,
+            unmappable: enc.unmappable,
+        }
+    }
+}
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -62,6 +93,9 @@ pub struct TdfFont {
     pub name: String,
     pub font_type: TdfFontType,
     pub spacing: i32,
+    /// Rows from the top of a glyph to the baseline; `None` detects it from the glyphs, see
+    /// [`TdfFont::detect_baseline`]. TDF files have no room for it, so it is not saved.
+    pub baseline: Option<usize>,
     // Overlay for programmatically constructed/modified glyphs.
     // Index 0 corresponds to '!'.
     glyphs_overlay: [Option<Glyph>; CHAR_TABLE_SIZE],
@@ -104,6 +138,7 @@ impl TdfFont {
             name: name.into(),
             font_type,
             spacing,
+            baseline: None,
             glyphs_overlay: std::array::from_fn(|_| None),
             lazy: None,
         }
@@ -165,6 +200,20 @@ impl TdfFont {
     }
 
     pub fn load_arc(bytes: Arc<[u8]>) -> Result<Vec<Self>> {
+        Ok(Self::parse_bundle(bytes)?.0)
+    }
+
+    /// Load the fonts of a TDF file; errors name the file, see [`FontError::File`].
+    pub fn load_file(path: &Path) -> Result<Vec<Self>> {
+        fs::read(path)
+            .map_err(FontError::from)
+            .and_then(|bytes| Self::load(&bytes))
+            .map_err(|e| e.in_file(path))
+    }
+
+    /// Parse the fonts of a bundle, also returning the offset of the bundle terminator or EOF
+    /// marker (or the data length if both are missing).
+    pub(crate) fn parse_bundle(bytes: Arc<[u8]>) -> Result<(Vec<Self>, usize)> {
         // Parse one or multiple fonts from bundle
         let b = bytes.as_ref();
         if b.len() < 20 {
@@ -189,117 +238,82 @@ impl TdfFont {
         o += 1;
         let mut fonts = Vec::new();
         while o < b.len() {
-            if b[o] == 0 {
+            // Bundle terminator, or the EOF marker of a SAUCE trailer
+            if b[o] == 0 || b[o] == CTRL_Z {
                 break;
-            } // bundle terminator
-            if o + 4 > b.len() {
-                return Err(FontError::TdfTruncated { field: "indicator" });
-            }
-            let indicator = u32::from_le_bytes(b[o..o + 4].try_into().unwrap());
-            if indicator != FONT_INDICATOR {
-                return Err(FontError::TdfFontIndicatorMismatch);
-            }
-            o += 4;
-            if o >= b.len() {
-                return Err(FontError::TdfTruncated {
-                    field: "name length",
-                });
-            }
-            let orig_len = b[o] as usize;
-            o += 1;
-            let mut name_len = orig_len.min(FONT_NAME_LEN_MAX);
-            if o + name_len > b.len() {
-                return Err(FontError::TdfTruncated { field: "name" });
             }
-            for i in 0..name_len {
-                if b[o + i] == 0 {
-                    name_len = i;
-                    break;
-                }
-            }
-            let name = String::from_utf8_lossy(&b[o..o + name_len]).into_owned();
-            o += FONT_NAME_LEN; // always skip full 12 bytes region
-            o += 4; // magic bytes
-            if o >= b.len() {
-                return Err(FontError::TdfTruncated { field: "font type" });
-            }
-            let font_type = match b[o] {
-                0 => TdfFontType::Outline,
-                1 => TdfFontType::Block,
-                2 => TdfFontType::Color,
-                other => return Err(FontError::TdfUnsupportedType(other)),
-            };
-            o += 1;
-            if o >= b.len() {
-                return Err(FontError::TdfTruncated { field: "spacing" });
-            }
-            let spacing = b[o] as i32;
-            o += 1;
-            if o + 2 > b.len() {
-                return Err(FontError::TdfTruncated {
-                    field: "block size",
-                });
-            }
-            let block_size = (b[o] as u16 | ((b[o + 1] as u16) << 8)) as usize;
-            o += 2;
-            if o + CHAR_TABLE_SIZE * 2 > b.len() {
-                return Err(FontError::TdfTruncated {
-                    field: "char table",
-                });
-            }
-            let mut lookup: [u16; CHAR_TABLE_SIZE] = [0u16; CHAR_TABLE_SIZE];
-            // We did one bounds check above; now do unchecked reads in the hot loop.
-            unsafe {
-                for i in 0..CHAR_TABLE_SIZE {
-                    let lo = *b.get_unchecked(o);
-                    let hi = *b.get_unchecked(o + 1);
-                    lookup[i] = u16::from_le_bytes([lo, hi]);
-                    o += 2;
-                }
-            }
-            if o + block_size > b.len() {
-                return Err(FontError::TdfTruncated {
-                    field: "glyph block",
+            let record =
+                parse_record(&bytes, o, None).map_err(|e| e.in_bundle_font(fonts.len()))?;
+            o = record.end;
+            fonts.push(record.font);
+        }
+        Ok((fonts, o.min(b.len())))
+    }
+
+    /// Load as many fonts as possible from a damaged bundle.
+    ///
+    /// Unlike [`TdfFont::load`] this never fails: records that cannot be decoded and garbage
+    /// between records are skipped by rescanning for the next font indicator, fonts whose
+    /// glyph block is cut off keep the glyphs that are complete. Everything left out is
+    /// listed in [`RecoveredTdf::skipped`], including unknown bytes after the last font
+    /// (anything but the bundle terminator, an EOF marker and a SAUCE record).
+    pub fn recover(bytes: &[u8]) -> RecoveredTdf {
+        Self::recover_arc(Arc::<[u8]>::from(bytes.to_vec()))
+    }
+
+    pub fn recover_arc(bytes: Arc<[u8]>) -> RecoveredTdf {
+        let b = bytes.as_ref();
+        let mut recovered = RecoveredTdf::default();
+        let header_ok = b.len() > 20
+            && b[0] as usize == THE_DRAW_FONT_ID.len() + 1
+            && &b[1..19] == THE_DRAW_FONT_ID
+            && b[19] == CTRL_Z;
+        let mut o = 20;
+        if !header_ok {
+            o = find_indicator(b, 0).unwrap_or(b.len());
+            recovered.skipped.push(SkippedRecord {
+                range: 0..o,
+                reason: FontError::TdfIdMismatch,
+            });
+        }
+        while let Some(start) = find_indicator(b, o) {
+            if start > o && !is_bundle_end(&b[o..start]) {
+                recovered.skipped.push(SkippedRecord {
+                    range: o..start,
+                    reason: FontError::TdfFontIndicatorMismatch { offset: o },
                 });
             }
-            // Validate lookup offsets are within block once, so glyph() can stay fast.
-            for off in lookup.iter().copied() {
-                if off == INVALID_GLYPH {
-                    continue;
+            match parse_record(&bytes, start, Some(&mut recovered.skipped)) {
+                Ok(record) => {
+                    if record.truncated {
+                        recovered.truncated.push(recovered.fonts.len());
+                    }
+                    recovered.fonts.push(record.font);
+                    o = record.end;
                 }
-                let off_usize = off as usize;
-                if off_usize >= block_size {
-                    return Err(FontError::TdfGlyphOutOfBounds {
-                        offset: off_usize,
-                        size: block_size,
+                Err(reason) => {
+                    let end = find_indicator(b, start + 1).unwrap_or(b.len());
+                    recovered.skipped.push(SkippedRecord {
+                        range: start..end,
+                        reason,
                     });
+                    o = end;
                 }
             }
-
-            let base = o; // start of glyph block
-            let glyph_block_end = o + block_size;
-            let cache: Arc<[OnceLock<Glyph>; CHAR_TABLE_SIZE]> =
-                Arc::new(std::array::from_fn(|_| OnceLock::new()));
-
-            let font = TdfFont {
-                name,
-                font_type,
-                spacing,
-                glyphs_overlay: std::array::from_fn(|_| None),
-                lazy: Some(LazyGlyphSource {
-                    bytes: bytes.clone(),
-                    font_type,
-                    glyph_block_base: base,
-                    glyph_block_end,
-                    lookup,
-                    cache,
-                }),
-            };
-
-            o += block_size;
-            fonts.push(font);
         }
-        Ok(fonts)
+        let tail = &b[o.min(b.len())..];
+        if !is_bundle_end(tail) && !is_known_trailer(tail) {
+            // Like `validate`, the bundle terminator isn't part of the garbage.
+            let start = b.len() - tail.strip_prefix(&[0]).unwrap_or(tail).len();
+            recovered.skipped.push(SkippedRecord {
+                range: start..b.len(),
+                reason: FontError::TdfTrailingBytes {
+                    offset: start,
+                    len: b.len() - start,
+                },
+            });
+        }
+        recovered
     }
 
     /// Iterate over all defined glyphs, yielding (char, &Glyph).
@@ -311,28 +325,41 @@ impl TdfFont {
         })
     }
 
+    /// Serialize with the default [`Cp437Options`]: transliterate, `?` for the rest.
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(self.to_bytes_with(Cp437Options::default())?.bytes)
+    }
+
+    /// Serialize, mapping characters to CP437 according to `options`.
+    pub fn to_bytes_with(&self, options: Cp437Options) -> Result<SavedTdf> {
+        let mut enc = Cp437Encoder::new(options);
         let mut out = Vec::new();
         out.push(THE_DRAW_FONT_ID.len() as u8 + 1);
         out.extend(THE_DRAW_FONT_ID);
         out.push(CTRL_Z);
-        self.append_font_data(&mut out)?;
-        Ok(out)
+        self.append_font_data(&mut out, &mut enc)?;
+        Ok(SavedTdf::new(out, enc))
     }
 
     pub fn serialize_bundle(fonts: &[TdfFont]) -> Result<Vec<u8>> {
+        Ok(Self::serialize_bundle_with(fonts, Cp437Options::default())?.bytes)
+    }
+
+    /// Serialize a bundle, mapping characters to CP437 according to `options`.
+    pub fn serialize_bundle_with(fonts: &[TdfFont], options: Cp437Options) -> Result<SavedTdf> {
+        let mut enc = Cp437Encoder::new(options);
         let mut out = Vec::new();
         out.push(THE_DRAW_FONT_ID.len() as u8 + 1);
         out.extend(THE_DRAW_FONT_ID);
         out.push(CTRL_Z);
         for f in fonts {
-            f.append_font_data(&mut out)?;
+            f.append_font_data(&mut out, &mut enc)?;
         }
         out.push(0); // terminator
-        Ok(out)
+        Ok(SavedTdf::new(out, enc))
     }
 
-    fn append_font_data(&self, out: &mut Vec<u8>) -> Result<()> {
+    fn append_font_data(&self, out: &mut Vec<u8>, enc: &mut Cp437Encoder) -> Result<()> {
         out.extend(u32::to_le_bytes(FONT_INDICATOR));
         if self.name.len() > FONT_NAME_LEN {
             return Err(FontError::TdfNameTooLong {
@@ -352,6 +379,7 @@ impl TdfFont {
         out.push(type_byte);
         out.push(self.spacing as u8);
         // build lookup + glyph data
+        let color = self.font_type == TdfFontType::Color;
         let mut lookup = Vec::new();
         let mut glyph_block = Vec::new();
         for i in 0..CHAR_TABLE_SIZE {
@@ -361,25 +389,31 @@ impl TdfFont {
                 glyph_block.push(g.width as u8);
                 glyph_block.push(g.height as u8);
                 for part in &g.parts {
-                    match part {
-                        GlyphPart::NewLine => glyph_block.push(13),
-                        GlyphPart::EndMarker => glyph_block.push(b'&'),
-                        GlyphPart::HardBlank => glyph_block.push(0xFF),
-                        GlyphPart::FillMarker => glyph_block.push(b'@'),
-                        GlyphPart::OutlineHole => glyph_block.push(b'O'),
-                        GlyphPart::OutlinePlaceholder(b) => glyph_block.push(*b),
-                        GlyphPart::Skip => glyph_block.push(b' '),
-                        GlyphPart::Char(c) => {
-                            let mapped = UNICODE_TO_CP437.get(c).copied().unwrap_or(b'?');
-                            glyph_block.push(mapped);
+                    // Every cell of a color font is a char/attribute pair.
+                    let (byte, attr) = match part {
+                        GlyphPart::NewLine => {
+                            glyph_block.push(13);
+                            continue;
                         }
-                        GlyphPart::AnsiChar { ch, fg, bg, blink } => {
-                            let mapped = UNICODE_TO_CP437.get(ch).copied().unwrap_or(b'?');
-                            glyph_block.push(mapped);
-                            glyph_block.push(
-                                ((bg & 0x07) << 4) | (fg & 0x0F) | if *blink { 0x80 } else { 0x00 },
-                            );
+                        GlyphPart::EndMarker => {
+                            glyph_block.push(b'&');
+                            continue;
                         }
+                        GlyphPart::HardBlank => (0xFF, DEFAULT_ATTR),
+                        GlyphPart::FillMarker => (b'@', DEFAULT_ATTR),
+                        GlyphPart::OutlineHole => (b'O', DEFAULT_ATTR),
+                        GlyphPart::OutlinePlaceholder(b) => (*b, DEFAULT_ATTR),
+                        GlyphPart::Skip => (b' ', TRANSPARENT_ATTR),
+                        GlyphPart::Char(c) => (enc.encode(*c)?, DEFAULT_ATTR),
+                        GlyphPart::AnsiChar { ch, fg, bg, blink } => (
+                            enc.encode(*ch)?,
+                            // Backgrounds 8-15 (iCE colors) share bit 7 with blink.
+                            ((bg & 0x0F) << 4) | (fg & 0x0F) | if *blink { 0x80 } else { 0x00 },
+                        ),
+                    };
+                    glyph_block.push(byte);
+                    if color {
+                        glyph_block.push(attr);
                     }
                 }
                 glyph_block.push(0); // terminator
@@ -415,6 +449,56 @@ impl TdfFont {
         Some(lazy.cache[idx].get_or_init(|| decode_glyph(lazy, idx)))
     }
 
+    /// Like [`TdfFont::glyph`], but fails on corrupt glyph data instead of returning an empty
+    /// or partial glyph.
+    ///
+    /// Returns `Ok(None)` for characters the font does not define.
+    ///
+    /// # Errors
+    ///
+    /// [`FontError::TdfGlyphOutOfBounds`] for offsets outside the glyph block,
+    /// [`FontError::TdfGlyphTruncated`] for a color cell without attribute byte,
+    /// [`FontError::TdfGlyphUnterminated`] for data running into the end of the block and
+    /// [`FontError::TdfGlyphSizeMismatch`] if the rows disagree with the declared size.
+    pub fn try_glyph(&self, ch: char) -> Result<Option<&Glyph>> {
+        let Some(idx) = tdf_index(ch) else {
+            return Ok(None);
+        };
+        if let Some(g) = self.glyphs_overlay[idx].as_ref() {
+            return Ok(Some(g));
+        }
+        let Some(lazy) = self.lazy.as_ref() else {
+            return Ok(None);
+        };
+        if lazy.lookup[idx] == INVALID_GLYPH {
+            return Ok(None);
+        }
+        let (glyph, error) = decode_glyph_checked(lazy, idx);
+        if let Some(error) = error {
+            return Err(error);
+        }
+        Ok(Some(lazy.cache[idx].get_or_init(|| glyph)))
+    }
+
+    /// Decode every glyph with [`TdfFont::try_glyph`], failing on the first corrupt one.
+    pub fn check_glyphs(&self) -> Result<()> {
+        for idx in 0..CHAR_TABLE_SIZE {
+            self.try_glyph(tdf_char(idx))?;
+        }
+        Ok(())
+    }
+
+    /// Load a bundle and decode all glyphs up front, see [`TdfFont::check_glyphs`].
+    ///
+    /// Fails where [`TdfFont::load`] would hand out empty or partial glyphs later.
+    pub fn load_checked(bytes: &[u8]) -> Result<Vec<Self>> {
+        let fonts = Self::load(bytes)?;
+        for (i, font) in fonts.iter().enumerate() {
+            font.check_glyphs().map_err(|e| e.in_bundle_font(i))?;
+        }
+        Ok(fonts)
+    }
+
     /// Get the size of a glyph (width, height)
     pub fn glyph_size(&self, ch: char) -> Option<(usize, usize)> {
         self.glyph(ch).map(|g| (g.width, g.height))
@@ -430,6 +514,51 @@ impl TdfFont {
         }
         max_h.max(1)
     }
+
+    /// Guess the baseline: the most common height of the uppercase letters, else of the
+    /// digits, else the height of the tallest glyph. Glyphs are top-aligned, so this is the
+    /// cap height.
+    pub fn detect_baseline(&self) -> usize {
+        self.common_height("ABCDEFGHIJKLMNOPQRSTUVWXYZ")
+            .or_else(|| self.common_height("0123456789"))
+            .unwrap_or_else(|| self.max_height())
+    }
+
+    /// Rows of the glyph for `ch` below the baseline.
+    ///
+    /// Only `g`, `j`, `p`, `q` and `y` have descenders: the rows they are taller than the
+    /// lowercase letters without ascenders or descenders (`i` for `j`, whose dot adds rows
+    /// on top). Fonts whose lowercase letters are as tall as the capitals have none.
+    pub fn descent(&self, ch: char) -> usize {
+        if !"gjpqy".contains(ch) {
+            return 0;
+        }
+        let Some((_, height)) = self.glyph_size(ch) else {
+            return 0;
+        };
+        let body = match ch {
+            'j' => self.glyph_size('i').map(|(_, h)| h),
+            _ => self.common_height("acemnorsuvwxz"),
+        };
+        body.map_or(0, |body| height.saturating_sub(body))
+    }
+
+    /// Most common glyph height among `chars`, the taller one on ties.
+    fn common_height(&self, chars: &str) -> Option<usize> {
+        let mut counts = [0usize; MAX_TDF_GLYPH_HEIGHT + 1];
+        for ch in chars.chars() {
+            if let Some((_, h)) = self.glyph_size(ch) {
+                counts[h.min(MAX_TDF_GLYPH_HEIGHT)] += 1;
+            }
+        }
+        counts
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| **n > 0)
+            .max_by_key(|(h, n)| (**n, *h))
+            .map(|(h, _)| h)
+    }
+
     pub fn font_type(&self) -> TdfFontType {
         self.font_type
     }
@@ -445,45 +574,376 @@ impl TdfFont {
             .as_ref()
             .is_some_and(|lazy| lazy.lookup[idx] != INVALID_GLYPH)
     }
+
+    /// Check the glyphs for inconsistencies, see [`crate::validate`].
+    ///
+    /// Glyphs loaded from a file are checked against their raw data, so diagnostics carry
+    /// the byte offset in the file.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut out = Vec::new();
+        // (start, end, char) of the raw glyph data, for the overlap check
+        let mut spans = Vec::new();
+        for idx in 0..CHAR_TABLE_SIZE {
+            let ch = tdf_char(idx);
+            if let Some(glyph) = &self.glyphs_overlay[idx] {
+                check_tdf_glyph(glyph, ch, self.font_type, &mut out);
+            } else if let Some(lazy) = &self.lazy {
+                if lazy.lookup[idx] != INVALID_GLYPH {
+                    if let Some(end) = validate_raw_glyph(lazy, idx, &mut out) {
+                        spans.push((lazy.glyph_block_base + lazy.lookup[idx] as usize, end, ch));
+                    }
+                }
+            }
+        }
+        // Glyphs sharing their data are fine, partly overlapping data is not.
+        spans.sort();
+        for pair in spans.windows(2) {
+            let ((start, end, prev), (next_start, _, ch)) = (pair[0], pair[1]);
+            if next_start != start && next_start < end {
+                out.push(Diagnostic::new(
+                    Issue::OverlappingGlyphs(prev),
+                    Some(ch),
+                    Some(next_start),
+                ));
+            }
+        }
+        out.sort_by_key(|d| d.ch);
+        out
+    }
+}
+
+/// Data left out by [`TdfFont::recover`].
+#[derive(Debug)]
+pub struct SkippedRecord {
+    /// Byte range in the file.
+    pub range: std::ops::Range<usize>,
+    pub reason: FontError,
+}
+
+/// The result of [`TdfFont::recover`].
+#[derive(Default)]
+pub struct RecoveredTdf {
+    pub fonts: Vec<TdfFont>,
+    /// Indices into `fonts` of fonts whose glyph block was cut off; glyphs past the end of
+    /// the data are missing.
+    pub truncated: Vec<usize>,
+    pub skipped: Vec<SkippedRecord>,
+}
+
+/// A font record parsed by [`parse_record`].
+struct Record {
+    font: TdfFont,
+    /// Offset after the record.
+    end: usize,
+    truncated: bool,
+}
+
+fn find_indicator(b: &[u8], from: usize) -> Option<usize> {
+    let indicator = FONT_INDICATOR.to_le_bytes();
+    b.get(from..)?
+        .windows(4)
+        .position(|w| w == indicator)
+        .map(|p| from + p)
+}
+
+/// Bundle terminator, EOF marker or padding: nothing worth reporting.
+fn is_bundle_end(gap: &[u8]) -> bool {
+    gap.iter().all(|&b| b == 0 || b == CTRL_Z)
+}
+
+/// Parse the font record at `o`.
+///
+/// Strict parsing (`skipped` is `None`) fails on any damage. In recovery mode a glyph block
+/// that is cut off or runs into the next font is shortened, and glyphs with offsets outside
+/// the block are dropped and added to `skipped`.
+fn parse_record(
+    bytes: &Arc<[u8]>,
+    mut o: usize,
+    mut skipped: Option<&mut Vec<SkippedRecord>>,
+) -> Result<Record> {
+    let b = bytes.as_ref();
+    let truncated = |field, offset| FontError::TdfTruncated { field, offset };
+    if o + 4 > b.len() {
+        return Err(truncated("indicator", o));
+    }
+    let indicator = u32::from_le_bytes(b[o..o + 4].try_into().unwrap());
+    if indicator != FONT_INDICATOR {
+        return Err(FontError::TdfFontIndicatorMismatch { offset: o });
+    }
+    o += 4;
+    if o >= b.len() {
+        return Err(truncated("name length", o));
+    }
+    let orig_len = b[o] as usize;
+    o += 1;
+    let mut name_len = orig_len.min(FONT_NAME_LEN_MAX);
+    if o + name_len > b.len() {
+        return Err(truncated("name", o));
+    }
+    for i in 0..name_len {
+        if b[o + i] == 0 {
+            name_len = i;
+            break;
+        }
+    }
+    let name = String::from_utf8_lossy(&b[o..o + name_len]).into_owned();
+    o += FONT_NAME_LEN; // always skip full 12 bytes region
+    o += 4; // magic bytes
+    if o >= b.len() {
+        return Err(truncated("font type", o));
+    }
+    let font_type = match b[o] {
+        0 => TdfFontType::Outline,
+        1 => TdfFontType::Block,
+        2 => TdfFontType::Color,
+        other => {
+            return Err(FontError::TdfUnsupportedType {
+                font_type: other,
+                offset: o,
+            })
+        }
+    };
+    o += 1;
+    if o >= b.len() {
+        return Err(truncated("spacing", o));
+    }
+    let spacing = b[o] as i32;
+    o += 1;
+    if o + 2 > b.len() {
+        return Err(truncated("block size", o));
+    }
+    let mut block_size = (b[o] as u16 | ((b[o + 1] as u16) << 8)) as usize;
+    o += 2;
+    if o + CHAR_TABLE_SIZE * 2 > b.len() {
+        return Err(truncated("char table", o));
+    }
+    let lookup_base = o;
+    let mut lookup: [u16; CHAR_TABLE_SIZE] = [0u16; CHAR_TABLE_SIZE];
+    // We did one bounds check above; now do unchecked reads in the hot loop.
+    unsafe {
+        for entry in lookup.iter_mut() {
+            let lo = *b.get_unchecked(o);
+            let hi = *b.get_unchecked(o + 1);
+            *entry = u16::from_le_bytes([lo, hi]);
+            o += 2;
+        }
+    }
+    // When recovering, a wrong block size must not swallow the next font.
+    let limit = match skipped {
+        Some(_) => find_indicator(b, o).unwrap_or(b.len()),
+        None => b.len(),
+    };
+    let mut cut_off = false;
+    if o + block_size > limit {
+        if skipped.is_none() {
+            return Err(truncated("glyph block", o));
+        }
+        block_size = limit - o;
+        cut_off = true;
+    }
+    // Validate lookup offsets are within block once, so glyph() can stay fast.
+    for (i, entry) in lookup.iter_mut().enumerate() {
+        if *entry == INVALID_GLYPH {
+            continue;
+        }
+        let off_usize = *entry as usize;
+        if off_usize >= block_size {
+            let reason = FontError::TdfGlyphOutOfBounds {
+                ch: tdf_char(i),
+                offset: off_usize,
+                size: block_size,
+            };
+            let Some(skipped) = skipped.as_deref_mut() else {
+                return Err(reason);
+            };
+            let at = lookup_base + i * 2;
+            skipped.push(SkippedRecord {
+                range: at..at + 2,
+                reason,
+            });
+            *entry = INVALID_GLYPH;
+        }
+    }
+
+    let base = o; // start of glyph block
+    let glyph_block_end = o + block_size;
+    let cache: Arc<[OnceLock<Glyph>; CHAR_TABLE_SIZE]> =
+        Arc::new(std::array::from_fn(|_| OnceLock::new()));
+
+    let font = TdfFont {
+        name,
+        font_type,
+        spacing,
+        baseline: None,
+        glyphs_overlay: std::array::from_fn(|_| None),
+        lazy: Some(LazyGlyphSource {
+            bytes: bytes.clone(),
+            font_type,
+            glyph_block_base: base,
+            glyph_block_end,
+            lookup,
+            cache,
+        }),
+    };
+    Ok(Record {
+        font,
+        end: glyph_block_end,
+        truncated: cut_off,
+    })
+}
+
+/// Check the raw data of a parsed glyph, returning the end of its data if it is readable.
+fn validate_raw_glyph(
+    lazy: &LazyGlyphSource,
+    idx: usize,
+    out: &mut Vec<Diagnostic>,
+) -> Option<usize> {
+    let b = lazy.bytes.as_ref();
+    let ch = tdf_char(idx);
+    let start = lazy.glyph_block_base + lazy.lookup[idx] as usize;
+    let mut report = |issue, offset| out.push(Diagnostic::new(issue, Some(ch), Some(offset)));
+    if start + 2 > lazy.glyph_block_end {
+        report(Issue::GlyphOutOfBounds, start);
+        return None;
+    }
+    let (declared_width, declared_height) = (b[start] as usize, b[start + 1] as usize);
+    let mut widths = vec![0usize];
+    let mut invalid_letter = None;
+    let mut p = start + 2;
+    let mut terminated = false;
+    while p < lazy.glyph_block_end {
+        let byte = b[p];
+        p += 1;
+        match byte {
+            0 => {
+                terminated = true;
+                break;
+            }
+            13 => widths.push(0),
+            b'&' => {}
+            _ => {
+                if lazy.font_type == TdfFontType::Color {
+                    if p >= lazy.glyph_block_end {
+                        break;
+                    }
+                    p += 1;
+                }
+                if lazy.font_type == TdfFontType::Outline
+                    && byte.is_ascii_alphabetic()
+                    && !(b'A'..=b'R').contains(&byte)
+                    && invalid_letter.is_none()
+                {
+                    invalid_letter = Some((byte, p - 1));
+                }
+                *widths.last_mut().unwrap() += 1;
+            }
+        }
+    }
+    if !terminated {
+        report(Issue::UnterminatedGlyph, start);
+    }
+    if let Some((byte, offset)) = invalid_letter {
+        report(Issue::InvalidOutlineChar(byte), offset);
+    }
+    let width = widths.iter().copied().max().unwrap_or(0);
+    let min = widths.iter().copied().min().unwrap_or(0);
+    let height = widths.len();
+    if lazy.font_type == TdfFontType::Block && declared_width > 0 && width == declared_width * 2 {
+        // Character/attribute pairs read as two cells each.
+        report(Issue::AttributesInBlockFont, start);
+    } else if width != declared_width {
+        report(
+            Issue::WidthMismatch {
+                declared: declared_width,
+                actual: width,
+            },
+            start,
+        );
+    }
+    if height != declared_height {
+        report(
+            Issue::HeightMismatch {
+                declared: declared_height,
+                actual: height,
+            },
+            start + 1,
+        );
+    }
+    if min != width {
+        report(Issue::RaggedRows { min, max: width }, start + 2);
+    }
+    if width > MAX_TDF_GLYPH_WIDTH || height > MAX_TDF_GLYPH_HEIGHT {
+        report(Issue::GlyphTooLarge { width, height }, start);
+    }
+    Some(p)
 }
 
 fn decode_glyph(lazy: &LazyGlyphSource, idx: usize) -> Glyph {
+    decode_glyph_checked(lazy, idx).0
+}
+
+/// Decode a glyph, also returning the first problem found in its data.
+///
+/// The glyph is decoded as far as possible either way: a glyph with a bad offset is empty,
+/// one that runs into the end of the glyph block keeps the cells read so far.
+fn decode_glyph_checked(lazy: &LazyGlyphSource, idx: usize) -> (Glyph, Option<FontError>) {
     let b = lazy.bytes.as_ref();
+    let ch = tdf_char(idx);
     let off = lazy.lookup[idx] as usize;
-    let mut p = lazy.glyph_block_base + off;
+    let start = lazy.glyph_block_base + off;
+    let mut p = start;
 
     // Width/height are inside the glyph block.
     if p + 2 > lazy.glyph_block_end {
-        return Glyph {
-            width: 0,
-            height: 0,
-            parts: Vec::new(),
+        let error = FontError::TdfGlyphOutOfBounds {
+            ch,
+            offset: off,
+            size: lazy.glyph_block_end - lazy.glyph_block_base,
         };
+        return (
+            Glyph {
+                width: 0,
+                height: 0,
+                parts: Vec::new(),
+            },
+            Some(error),
+        );
     }
 
     let width = unsafe { *b.get_unchecked(p) as usize };
     let height = unsafe { *b.get_unchecked(p + 1) as usize };
     p += 2;
 
+    let mut error = None;
+    let mut terminated = false;
+    // Cells per row, to compare with the declared size
+    let mut rows = vec![0usize];
     let mut parts = Vec::with_capacity(width.saturating_mul(height).saturating_add(height));
     while p < lazy.glyph_block_end {
         let ch = unsafe { *b.get_unchecked(p) };
         p += 1;
         if ch == 0 {
+            terminated = true;
             break;
         }
         if ch == 13 {
             parts.push(GlyphPart::NewLine);
+            rows.push(0);
             continue;
         }
         if ch == b'&' {
             parts.push(GlyphPart::EndMarker);
             continue;
         }
+        *rows.last_mut().unwrap() += 1;
 
         match lazy.font_type {
             TdfFontType::Color => {
                 if p >= lazy.glyph_block_end {
+                    error = Some(FontError::TdfGlyphTruncated {
+                        ch: tdf_char(idx),
+                        offset: p - 1,
+                    });
                     break;
                 }
                 let attr = unsafe { *b.get_unchecked(p) };
@@ -491,9 +951,9 @@ fn decode_glyph(lazy: &LazyGlyphSource, idx: usize) -> Glyph {
                 let fg = attr & 0x0F;
                 let bg = (attr >> 4) & 0x07;
                 let blink = (attr & 0x80) != 0;
-                if ch == 0xFF {
-                    parts.push(GlyphPart::HardBlank);
-                } else if ch == b' ' {
+                // Spaces and hard blanks keep their attribute so solid background fills stay
+                // visible; only black-on-black spaces are transparent.
+                if ch == b' ' && attr == TRANSPARENT_ATTR {
                     parts.push(GlyphPart::Skip);
                 } else {
                     let uc = CP437_TO_UNICODE[ch as usize];
@@ -530,11 +990,25 @@ fn decode_glyph(lazy: &LazyGlyphSource, idx: usize) -> Glyph {
         }
     }
 
-    Glyph {
-        width,
-        height,
-        parts,
+    if error.is_none() && !terminated {
+        error = Some(FontError::TdfGlyphUnterminated { ch, offset: start });
+    }
+    let actual = (rows.iter().copied().max().unwrap_or(0), rows.len());
+    if error.is_none() && actual != (width, height) {
+        error = Some(FontError::TdfGlyphSizeMismatch {
+            ch,
+            declared: (width, height),
+            actual,
+        });
     }
+    (
+        Glyph {
+            width,
+            height,
+            parts,
+        },
+        error,
+    )
 }
 
 pub const CP437_TO_UNICODE: [char; 256] = [
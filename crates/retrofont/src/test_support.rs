@@ -3,7 +3,34 @@
 //! This module provides helper types and functions that are useful for testing
 //! font rendering, but are not part of the public API.
 
-use crate::{Cell, FontError, FontTarget};
+use crate::{Cell, FontError, FontTarget, Glyph, GlyphPart};
+
+/// Build a glyph from text rows.
+///
+/// Each character becomes a [`GlyphPart::Char`], except `.` for a transparent cell
+/// ([`GlyphPart::Skip`]) and `&` for an end marker. The size is taken from the rows.
+pub fn glyph(rows: &[&str]) -> Glyph {
+    let mut parts = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            parts.push(GlyphPart::NewLine);
+        }
+        parts.extend(row.chars().map(|c| match c {
+            '.' => GlyphPart::Skip,
+            '&' => GlyphPart::EndMarker,
+            c => GlyphPart::Char(c),
+        }));
+    }
+    Glyph {
+        width: rows
+            .iter()
+            .map(|r| r.chars().filter(|c| *c != '&').count())
+            .max()
+            .unwrap_or(0),
+        height: rows.len(),
+        parts,
+    }
+}
 
 /// A memory buffer target useful for tests.
 ///
@@ -0,0 +1,403 @@
+//! Geometric glyph transforms for generating font variants.
+//!
+//! Mirroring and rotating move the cells and also replace directional characters: box
+//! drawing lines, half and quarter blocks, slashes and brackets are swapped for their
+//! mirrored or rotated counterparts (`▌` ↔ `▐`, `┌` ↔ `┐`), outline placeholders of TDF
+//! outline fonts for the placeholder of the mirrored edge. Scaling stretches lines and blocks
+//! instead of repeating them.
+use crate::{
+    error::{FontError, Result},
+    figlet::FigletFont,
+    glyph::{Glyph, GlyphPart},
+    grid::GlyphGrid,
+    tdf::{TdfFont, MAX_TDF_GLYPH_HEIGHT, MAX_TDF_GLYPH_WIDTH},
+};
+
+/// Half and quarter blocks by the quarters they cover: top-left 8, top-right 4,
+/// bottom-left 2, bottom-right 1.
+pub(crate) const QUARTER_BLOCKS: [char; 16] = [
+    ' ', '▗', '▖', '▄', '▝', '▐', '▞', '▟', '▘', '▚', '▌', '▙', '▀', '▜', '▛', '█',
+];
+
+/// Box drawing characters by their arms (up, right, down, left): 1 single, 2 double, 3 heavy.
+const BOX_ARMS: [(char, [u8; 4]); 55] = [
+    ('─', [0, 1, 0, 1]),
+    ('│', [1, 0, 1, 0]),
+    ('┌', [0, 1, 1, 0]),
+    ('┐', [0, 0, 1, 1]),
+    ('└', [1, 1, 0, 0]),
+    ('┘', [1, 0, 0, 1]),
+    ('├', [1, 1, 1, 0]),
+    ('┤', [1, 0, 1, 1]),
+    ('┬', [0, 1, 1, 1]),
+    ('┴', [1, 1, 0, 1]),
+    ('┼', [1, 1, 1, 1]),
+    ('═', [0, 2, 0, 2]),
+    ('║', [2, 0, 2, 0]),
+    ('╒', [0, 2, 1, 0]),
+    ('╓', [0, 1, 2, 0]),
+    ('╔', [0, 2, 2, 0]),
+    ('╕', [0, 0, 1, 2]),
+    ('╖', [0, 0, 2, 1]),
+    ('╗', [0, 0, 2, 2]),
+    ('╘', [1, 2, 0, 0]),
+    ('╙', [2, 1, 0, 0]),
+    ('╚', [2, 2, 0, 0]),
+    ('╛', [1, 0, 0, 2]),
+    ('╜', [2, 0, 0, 1]),
+    ('╝', [2, 0, 0, 2]),
+    ('╞', [1, 2, 1, 0]),
+    ('╟', [2, 1, 2, 0]),
+    ('╠', [2, 2, 2, 0]),
+    ('╡', [1, 0, 1, 2]),
+    ('╢', [2, 0, 2, 1]),
+    ('╣', [2, 0, 2, 2]),
+    ('╤', [0, 2, 1, 2]),
+    ('╥', [0, 1, 2, 1]),
+    ('╦', [0, 2, 2, 2]),
+    ('╧', [1, 2, 0, 2]),
+    ('╨', [2, 1, 0, 1]),
+    ('╩', [2, 2, 0, 2]),
+    ('╪', [1, 2, 1, 2]),
+    ('╫', [2, 1, 2, 1]),
+    ('╬', [2, 2, 2, 2]),
+    ('━', [0, 3, 0, 3]),
+    ('┃', [3, 0, 3, 0]),
+    ('┏', [0, 3, 3, 0]),
+    ('┓', [0, 0, 3, 3]),
+    ('┗', [3, 3, 0, 0]),
+    ('┛', [3, 0, 0, 3]),
+    ('┣', [3, 3, 3, 0]),
+    ('┫', [3, 0, 3, 3]),
+    ('┳', [0, 3, 3, 3]),
+    ('┻', [3, 3, 0, 3]),
+    ('╋', [3, 3, 3, 3]),
+    ('╴', [0, 0, 0, 1]),
+    ('╵', [1, 0, 0, 0]),
+    ('╶', [0, 1, 0, 0]),
+    ('╷', [0, 0, 1, 0]),
+];
+
+/// Other characters swapped by a horizontal mirror.
+const MIRROR_HORIZONTAL: [(char, char); 12] = [
+    ('/', '\\'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('►', '◄'),
+    ('▶', '◀'),
+    ('╱', '╲'),
+    ('▏', '▕'),
+    ('╭', '╮'),
+    ('╰', '╯'),
+    ('«', '»'),
+];
+
+/// Other characters swapped by a vertical mirror.
+const MIRROR_VERTICAL: [(char, char); 6] = [
+    ('/', '\\'),
+    ('╱', '╲'),
+    ('▲', '▼'),
+    ('╭', '╰'),
+    ('╮', '╯'),
+    ('▔', '▁'),
+];
+
+/// Other characters and what they become after a clockwise quarter turn.
+const ROTATE: [(char, char); 14] = [
+    ('/', '\\'),
+    ('\\', '/'),
+    ('╱', '╲'),
+    ('╲', '╱'),
+    ('-', '|'),
+    ('|', '-'),
+    ('▲', '►'),
+    ('►', '▼'),
+    ('▼', '◄'),
+    ('◄', '▲'),
+    ('╭', '╮'),
+    ('╮', '╯'),
+    ('╯', '╰'),
+    ('╰', '╭'),
+];
+
+/// Outline placeholders `A`-`N` after mirroring and rotating. `A`/`B` are the top and
+/// bottom edges, `C`/`D` the left and right ones, `E`-`L` corners and `M`/`N` junctions.
+const OUTLINE_MIRROR_HORIZONTAL: &[u8; 14] = b"ABDCFEHGJILKNM";
+const OUTLINE_MIRROR_VERTICAL: &[u8; 14] = b"BACDKLIJGHEFMN";
+const OUTLINE_ROTATE: &[u8; 14] = b"DCABFLHJGIEKMN";
+/// The two cells a placeholder becomes when doubling the width, `@` is the fill.
+const OUTLINE_SCALE_X: [&[u8; 2]; 14] = [
+    b"AA", b"BB", b"C@", b"@D", b"EA", b"AF", b"GB", b"BH", b"IA", b"AJ", b"KB", b"BL", b"AM",
+    b"NA",
+];
+/// The two cells a placeholder becomes when doubling the height.
+const OUTLINE_SCALE_Y: [&[u8; 2]; 14] = [
+    b"A@", b"@B", b"CC", b"DD", b"EC", b"FD", b"GC", b"HD", b"DI", b"CJ", b"CK", b"DL", b"MM",
+    b"NN",
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Transform {
+    /// Mirror left to right.
+    FlipHorizontal,
+    /// Mirror top to bottom.
+    FlipVertical,
+    /// Rotate a quarter turn clockwise. Text mode cells are about twice as tall as wide, so
+    /// the result looks stretched.
+    Rotate90,
+    /// Double the width.
+    ScaleX2,
+    /// Double the height.
+    ScaleY2,
+    /// Slant to the right, one column for every two rows above the bottom row.
+    Italic,
+}
+
+/// Apply `transform` to a glyph. End markers are dropped.
+pub fn transform_glyph(glyph: &Glyph, transform: Transform) -> Glyph {
+    let grid = GlyphGrid::from_glyph(glyph);
+    let (width, height) = (grid.width(), grid.height());
+    let cell = |x: usize, y: usize| &grid.rows()[y][x];
+    let mut out = GlyphGrid::new(0, 0);
+    match transform {
+        Transform::FlipHorizontal => {
+            out.resize(width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    out.set(width - 1 - x, y, map_cell(cell(x, y), transform));
+                }
+            }
+        }
+        Transform::FlipVertical => {
+            out.resize(width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    out.set(x, height - 1 - y, map_cell(cell(x, y), transform));
+                }
+            }
+        }
+        Transform::Rotate90 => {
+            out.resize(height, width);
+            for y in 0..height {
+                for x in 0..width {
+                    out.set(height - 1 - y, x, map_cell(cell(x, y), transform));
+                }
+            }
+        }
+        Transform::ScaleX2 | Transform::ScaleY2 => {
+            let vertical = transform == Transform::ScaleY2;
+            if vertical {
+                out.resize(width, height * 2);
+            } else {
+                out.resize(width * 2, height);
+            }
+            for y in 0..height {
+                for x in 0..width {
+                    let [first, second] = scale_cell(cell(x, y), vertical);
+                    if vertical {
+                        out.set(x, y * 2, first);
+                        out.set(x, y * 2 + 1, second);
+                    } else {
+                        out.set(x * 2, y, first);
+                        out.set(x * 2 + 1, y, second);
+                    }
+                }
+            }
+        }
+        Transform::Italic => {
+            let shift = |y: usize| (height - 1 - y) / 2;
+            out.resize(width + height.saturating_sub(1) / 2, height);
+            for y in 0..height {
+                for x in 0..width {
+                    out.set(x + shift(y), y, cell(x, y).clone());
+                }
+            }
+        }
+    }
+    out.to_glyph()
+}
+
+/// A copy of `font` with every glyph transformed.
+///
+/// # Errors
+///
+/// Returns [`FontError::TdfGlyphTooLarge`] for the first glyph that grows beyond
+/// [`MAX_TDF_GLYPH_WIDTH`] x [`MAX_TDF_GLYPH_HEIGHT`], as rotating, scaling or slanting
+/// large glyphs can.
+pub fn transform_tdf(font: &TdfFont, transform: Transform) -> Result<TdfFont> {
+    let mut spacing = font.spacing;
+    if transform == Transform::ScaleX2 {
+        spacing *= 2;
+    }
+    let mut out = TdfFont::new(font.name.clone(), font.font_type(), spacing);
+    out.baseline = transform_baseline(font.baseline, transform);
+    for (ch, glyph) in font.iter_glyphs() {
+        let glyph = transform_glyph(glyph, transform);
+        if glyph.width > MAX_TDF_GLYPH_WIDTH || glyph.height > MAX_TDF_GLYPH_HEIGHT {
+            return Err(FontError::TdfGlyphTooLarge {
+                ch,
+                width: glyph.width,
+                height: glyph.height,
+            });
+        }
+        out.add_glyph(ch, glyph);
+    }
+    Ok(out)
+}
+
+/// A copy of `font` with every glyph transformed.
+pub fn transform_figlet(font: &FigletFont, transform: Transform) -> FigletFont {
+    let mut out = FigletFont::new(font.name.clone());
+    out.format = font.format;
+    out.header = font.header.clone();
+    out.comments = font.comments.clone();
+    out.hard_blank = font.hard_blank;
+    out.baseline = transform_baseline(font.baseline, transform);
+    for (ch, glyph) in font.iter_glyphs() {
+        out.add_glyph(ch, transform_glyph(glyph, transform));
+    }
+    out
+}
+
+/// The baseline survives transforms that keep the rows; otherwise it is detected again.
+fn transform_baseline(baseline: Option<usize>, transform: Transform) -> Option<usize> {
+    match transform {
+        Transform::FlipHorizontal | Transform::ScaleX2 | Transform::Italic => baseline,
+        Transform::ScaleY2 => baseline.map(|b| b * 2),
+        Transform::FlipVertical | Transform::Rotate90 => None,
+    }
+}
+
+fn map_cell(part: &GlyphPart, transform: Transform) -> GlyphPart {
+    match part {
+        GlyphPart::Char(ch) => GlyphPart::Char(map_char(*ch, transform)),
+        GlyphPart::AnsiChar { ch, fg, bg, blink } => GlyphPart::AnsiChar {
+            ch: map_char(*ch, transform),
+            fg: *fg,
+            bg: *bg,
+            blink: *blink,
+        },
+        GlyphPart::OutlinePlaceholder(b @ b'A'..=b'N') => {
+            let table = match transform {
+                Transform::FlipHorizontal => OUTLINE_MIRROR_HORIZONTAL,
+                Transform::FlipVertical => OUTLINE_MIRROR_VERTICAL,
+                Transform::Rotate90 => OUTLINE_ROTATE,
+                _ => return part.clone(),
+            };
+            GlyphPart::OutlinePlaceholder(table[(b - b'A') as usize])
+        }
+        part => part.clone(),
+    }
+}
+
+fn map_char(ch: char, transform: Transform) -> char {
+    if let Some(arms) = box_arms(ch) {
+        let [u, r, d, l] = arms;
+        let arms = match transform {
+            Transform::FlipHorizontal => [u, l, d, r],
+            Transform::FlipVertical => [d, r, u, l],
+            Transform::Rotate90 => [l, u, r, d],
+            _ => arms,
+        };
+        return box_char(arms).unwrap_or(ch);
+    }
+    if let Some(mask) = QUARTER_BLOCKS.iter().position(|&c| c == ch) {
+        // Bits: top-left 8, top-right 4, bottom-left 2, bottom-right 1
+        let bit = |b: usize, to: usize| if mask & b != 0 { to } else { 0 };
+        let mask = match transform {
+            Transform::FlipHorizontal => bit(8, 4) | bit(4, 8) | bit(2, 1) | bit(1, 2),
+            Transform::FlipVertical => bit(8, 2) | bit(4, 1) | bit(2, 8) | bit(1, 4),
+            Transform::Rotate90 => bit(8, 4) | bit(4, 1) | bit(1, 2) | bit(2, 8),
+            _ => mask,
+        };
+        return QUARTER_BLOCKS[mask];
+    }
+    let swap = |pairs: &[(char, char)]| {
+        pairs.iter().find_map(|&(a, b)| {
+            if a == ch {
+                Some(b)
+            } else if b == ch {
+                Some(a)
+            } else {
+                None
+            }
+        })
+    };
+    match transform {
+        Transform::FlipHorizontal => swap(&MIRROR_HORIZONTAL),
+        Transform::FlipVertical => swap(&MIRROR_VERTICAL),
+        Transform::Rotate90 => ROTATE.iter().find(|(a, _)| *a == ch).map(|(_, b)| *b),
+        _ => None,
+    }
+    .unwrap_or(ch)
+}
+
+/// The two cells a cell becomes when doubling the width (or the height if `vertical`):
+/// lines continue into the new cell, blocks are split by their halves.
+fn scale_cell(part: &GlyphPart, vertical: bool) -> [GlyphPart; 2] {
+    let ch = match part {
+        GlyphPart::Char(ch) | GlyphPart::AnsiChar { ch, .. } => *ch,
+        GlyphPart::OutlinePlaceholder(b @ b'A'..=b'N') => {
+            let table = if vertical {
+                OUTLINE_SCALE_Y
+            } else {
+                OUTLINE_SCALE_X
+            };
+            return table[(b - b'A') as usize].map(|c| match c {
+                b'@' => GlyphPart::FillMarker,
+                c => GlyphPart::OutlinePlaceholder(c),
+            });
+        }
+        part => return [part.clone(), part.clone()],
+    };
+    let with_char = |c: char| match part {
+        GlyphPart::AnsiChar { fg, bg, blink, .. } => GlyphPart::AnsiChar {
+            ch: c,
+            fg: *fg,
+            bg: *bg,
+            blink: *blink,
+        },
+        _ if c == ' ' => GlyphPart::Skip,
+        _ => GlyphPart::Char(c),
+    };
+    if let Some([u, r, d, l]) = box_arms(ch) {
+        // Arms pointing into the new cell continue there as a straight line.
+        let (into_second, into_first) = if vertical { (d, u) } else { (r, l) };
+        let line = |w| {
+            let arms = if vertical { [w, 0, w, 0] } else { [0, w, 0, w] };
+            box_char(arms).map_or(with_char(ch), with_char)
+        };
+        return if into_second > 0 {
+            [with_char(ch), line(into_second)]
+        } else if into_first > 0 {
+            [line(into_first), with_char(ch)]
+        } else {
+            [with_char(ch), with_char(' ')]
+        };
+    }
+    if let Some(mask) = QUARTER_BLOCKS.iter().position(|&c| c == ch) {
+        let bit = |b: usize, to: usize| if mask & b != 0 { to } else { 0 };
+        let (first, second) = if vertical {
+            // Each row of quarters fills a whole cell.
+            (bit(8, 10) | bit(4, 5), bit(2, 10) | bit(1, 5))
+        } else {
+            (bit(8, 12) | bit(2, 3), bit(4, 12) | bit(1, 3))
+        };
+        return [
+            with_char(QUARTER_BLOCKS[first]),
+            with_char(QUARTER_BLOCKS[second]),
+        ];
+    }
+    [part.clone(), part.clone()]
+}
+
+fn box_arms(ch: char) -> Option<[u8; 4]> {
+    BOX_ARMS.iter().find(|(c, _)| *c == ch).map(|(_, a)| *a)
+}
+
+fn box_char(arms: [u8; 4]) -> Option<char> {
+    BOX_ARMS.iter().find(|(_, a)| *a == arms).map(|(c, _)| *c)
+}
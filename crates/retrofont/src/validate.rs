@@ -0,0 +1,249 @@
+//! Checking fonts for structural problems.
+//!
+//! Fonts found in the wild are often subtly broken: declared glyph sizes disagree with the
+//! glyph data, rows are ragged or outline fonts use letters that are no outline placeholders.
+//! Most of them still load and render somehow; [`TdfFont::validate`] and
+//! [`FigletFont::validate`] list what is wrong as [`Diagnostic`]s, and [`validate`] checks a
+//! whole file including the bytes around the fonts.
+use std::fmt;
+
+use crate::{
+    bundle::is_known_trailer,
+    error::{FontError, Result},
+    figlet::FigletFont,
+    glyph::{Glyph, GlyphPart},
+    tdf::{TdfFont, TdfFontType, MAX_TDF_GLYPH_HEIGHT, MAX_TDF_GLYPH_WIDTH},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    /// Loads, but likely renders differently than intended.
+    Warning,
+    /// Data that is lost or cannot be read.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// What is wrong, see [`Issue::severity`] for how bad it is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Issue {
+    /// The width stored in the glyph header differs from its widest row.
+    WidthMismatch { declared: usize, actual: usize },
+    /// The height stored in the glyph (or FIGlet) header differs from the number of rows.
+    HeightMismatch { declared: usize, actual: usize },
+    /// Rows of one glyph have different widths.
+    RaggedRows { min: usize, max: usize },
+    /// Glyph larger than TDF allows (30x12).
+    GlyphTooLarge { width: usize, height: usize },
+    /// A letter in an outline font that is no placeholder (`A`-`R`).
+    InvalidOutlineChar(u8),
+    /// Color attributes in a Block or Outline font, which has no room for them.
+    AttributesInBlockFont,
+    /// The glyph data overlaps the data of another glyph.
+    OverlappingGlyphs(char),
+    /// The glyph data runs into the end of the glyph block without a terminator.
+    UnterminatedGlyph,
+    /// The glyph offset points past the glyph block.
+    GlyphOutOfBounds,
+    /// A character every FIGlet font has to define is missing.
+    MissingChar,
+    /// A FIGlet glyph line (1-based line number) without `@` end mark; loading stops there.
+    MissingEndMark { line: usize },
+    /// Bytes after the bundle that are no EOF marker or SAUCE record.
+    TrailingBytes(usize),
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Issue::WidthMismatch { .. }
+            | Issue::HeightMismatch { .. }
+            | Issue::RaggedRows { .. }
+            | Issue::AttributesInBlockFont
+            | Issue::OverlappingGlyphs(_)
+            | Issue::MissingChar
+            | Issue::TrailingBytes(_) => Severity::Warning,
+            Issue::GlyphTooLarge { .. }
+            | Issue::InvalidOutlineChar(_)
+            | Issue::UnterminatedGlyph
+            | Issue::GlyphOutOfBounds
+            | Issue::MissingEndMark { .. } => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::WidthMismatch { declared, actual } => {
+                write!(f, "declared width {declared}, widest row is {actual}")
+            }
+            Issue::HeightMismatch { declared, actual } => {
+                write!(f, "declared height {declared}, glyph has {actual} rows")
+            }
+            Issue::RaggedRows { min, max } => {
+                write!(f, "ragged rows ({min} to {max} cells)")
+            }
+            Issue::GlyphTooLarge { width, height } => write!(
+                f,
+                "glyph is {width}x{height}, TDF allows {MAX_TDF_GLYPH_WIDTH}x{MAX_TDF_GLYPH_HEIGHT}"
+            ),
+            Issue::InvalidOutlineChar(b) => {
+                write!(f, "'{}' is no outline placeholder", *b as char)
+            }
+            Issue::AttributesInBlockFont => write!(f, "color attributes in a monochrome font"),
+            Issue::OverlappingGlyphs(ch) => write!(f, "glyph data overlaps {ch:?}"),
+            Issue::UnterminatedGlyph => write!(f, "glyph data is not terminated"),
+            Issue::GlyphOutOfBounds => write!(f, "glyph offset outside the glyph block"),
+            Issue::MissingChar => write!(f, "required character is missing"),
+            Issue::MissingEndMark { line } => write!(
+                f,
+                "line {line} is missing the @ end mark, the rest of the font is not loaded"
+            ),
+            Issue::TrailingBytes(len) => write!(f, "{len} unknown bytes after the bundle"),
+        }
+    }
+}
+
+/// One problem found in a font.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Index of the font in a TDF bundle, set by [`validate`].
+    pub font: Option<usize>,
+    pub ch: Option<char>,
+    /// Byte offset in the font file; `None` for glyphs that were not loaded from a file.
+    pub offset: Option<usize>,
+    pub issue: Issue,
+}
+
+impl Diagnostic {
+    pub fn new(issue: Issue, ch: Option<char>, offset: Option<usize>) -> Self {
+        Self {
+            severity: issue.severity(),
+            font: None,
+            ch,
+            offset,
+            issue,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.severity)?;
+        if let Some(font) = self.font {
+            write!(f, " [font #{}]", font + 1)?;
+        }
+        if let Some(ch) = self.ch {
+            write!(f, " {ch:?}")?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " @{offset:#06x}")?;
+        }
+        write!(f, ": {}", self.issue)
+    }
+}
+
+/// Validate the font file in `bytes`, TDF bundle or FIGlet font.
+///
+/// # Errors
+///
+/// Fails like [`crate::Font::load`] if the file cannot be parsed at all.
+pub fn validate(bytes: &[u8]) -> Result<Vec<Diagnostic>> {
+    if bytes.starts_with(b"flf2a") || bytes.starts_with(b"tlf2a") {
+        return Ok(FigletFont::load(bytes)?.validate());
+    }
+    if bytes.len() < 19 || bytes[0] != 0x13 || &bytes[1..19] != b"TheDraw FONTS file" {
+        return Err(FontError::UnrecognizedFormat);
+    }
+    let (fonts, end) = TdfFont::parse_bundle(bytes.into())?;
+    let mut out = Vec::new();
+    for (i, font) in fonts.iter().enumerate() {
+        out.extend(
+            font.validate()
+                .into_iter()
+                .map(|d| Diagnostic { font: Some(i), ..d }),
+        );
+    }
+    if !is_known_trailer(&bytes[end..]) {
+        let trailer = bytes[end..].strip_prefix(&[0]).unwrap_or(&bytes[end..]);
+        out.push(Diagnostic::new(
+            Issue::TrailingBytes(trailer.len()),
+            None,
+            Some(bytes.len() - trailer.len()),
+        ));
+    }
+    Ok(out)
+}
+
+/// Checks shared by decoded glyphs of all formats: ragged rows and colors in monochrome
+/// fonts.
+pub(crate) fn check_glyph(glyph: &Glyph, ch: char, colored: bool, out: &mut Vec<Diagnostic>) {
+    let widths = row_widths(&glyph.parts);
+    let (min, max) = (
+        widths.iter().copied().min().unwrap_or(0),
+        widths.iter().copied().max().unwrap_or(0),
+    );
+    if min != max {
+        out.push(Diagnostic::new(
+            Issue::RaggedRows { min, max },
+            Some(ch),
+            None,
+        ));
+    }
+    let has_colors = glyph
+        .parts
+        .iter()
+        .any(|p| matches!(p, GlyphPart::AnsiChar { .. }));
+    if has_colors && !colored {
+        out.push(Diagnostic::new(
+            Issue::AttributesInBlockFont,
+            Some(ch),
+            None,
+        ));
+    }
+}
+
+/// Checks for glyphs of a TDF font that were added in memory.
+pub(crate) fn check_tdf_glyph(
+    glyph: &Glyph,
+    ch: char,
+    font_type: TdfFontType,
+    out: &mut Vec<Diagnostic>,
+) {
+    check_glyph(glyph, ch, font_type == TdfFontType::Color, out);
+    let widths = row_widths(&glyph.parts);
+    let (width, height) = (widths.iter().copied().max().unwrap_or(0), widths.len());
+    if width > MAX_TDF_GLYPH_WIDTH || height > MAX_TDF_GLYPH_HEIGHT {
+        out.push(Diagnostic::new(
+            Issue::GlyphTooLarge { width, height },
+            Some(ch),
+            None,
+        ));
+    }
+}
+
+/// Cells per row; end markers take no cell.
+fn row_widths(parts: &[GlyphPart]) -> Vec<usize> {
+    let mut widths = vec![0];
+    for part in parts {
+        match part {
+            GlyphPart::NewLine => widths.push(0),
+            GlyphPart::EndMarker => {}
+            _ => *widths.last_mut().unwrap() += 1,
+        }
+    }
+    widths
+}
@@ -0,0 +1,65 @@
+use retrofont::{
+    tdf::{TdfFont, TdfFontType},
+    test_support::glyph,
+    Canvas, Font, Glyph, GlyphPart, RenderOptions, VerticalAlign,
+};
+
+/// Capitals are 3 rows, lowercase 2 rows with descenders reaching one row lower.
+fn mixed_font() -> TdfFont {
+    let mut font = TdfFont::new("MIXED", TdfFontType::Block, 0);
+    font.add_glyph('A', glyph(&["A", "A", "A"]));
+    font.add_glyph('B', glyph(&["B", "B", "B"]));
+    font.add_glyph('a', glyph(&["a", "a"]));
+    font.add_glyph('g', glyph(&["g", "g", "g"]));
+    // `glyph` reads '.' as a transparent cell
+    font.add_glyph(
+        '.',
+        Glyph {
+            width: 1,
+            height: 1,
+            parts: vec![GlyphPart::Char('.')],
+        },
+    );
+    font.add_glyph('|', glyph(&["|", "|", "|", "|", "|"]));
+    font
+}
+
+fn text(canvas: &Canvas) -> Vec<String> {
+    canvas
+        .lines()
+        .iter()
+        .map(|line| line.iter().map(|c| c.map_or(' ', |c| c.ch)).collect())
+        .collect()
+}
+
+fn render(font: &TdfFont, s: &str) -> Vec<String> {
+    let options = RenderOptions::default().align(VerticalAlign::Baseline);
+    text(&Canvas::render_text(&Font::Tdf(font.clone()), s, &options).unwrap())
+}
+
+#[test]
+fn detect_baseline_and_descenders() {
+    let font = mixed_font();
+    assert_eq!(font.detect_baseline(), 3);
+    assert_eq!(font.descent('g'), 1);
+    assert_eq!(font.descent('a'), 0);
+    assert_eq!(font.descent('A'), 0);
+
+    let mut font = font;
+    font.baseline = Some(2);
+    assert_eq!(Font::Tdf(font).baseline(), 2);
+}
+
+#[test]
+fn baseline_layout() {
+    let font = mixed_font();
+    assert_eq!(render(&font, "Aag."), ["A", "Aag", "Aag.", "  g"]);
+
+    // Top alignment stays the default.
+    let canvas =
+        Canvas::render_text(&Font::Tdf(font.clone()), "A.", &RenderOptions::default()).unwrap();
+    assert_eq!(text(&canvas), ["A.", "A", "A"]);
+
+    // Glyphs reaching above the capitals push the line down.
+    assert_eq!(render(&font, "|A."), ["|", "|", "|A", "|A", "|A."]);
+}
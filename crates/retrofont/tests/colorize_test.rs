@@ -0,0 +1,166 @@
+use retrofont::{
+    colorize::{CellColors, ColorFill, Colorizer, GradientDirection},
+    output::to_ansi_string,
+    tdf::{TdfFont, TdfFontType},
+    Canvas, Color, Font, Glyph, GlyphPart, Palette, RenderOptions, Rgb,
+};
+
+fn block_font() -> Font {
+    let mut font = TdfFont::new("BLK", TdfFontType::Block, 0);
+    font.add_glyph(
+        'A',
+        Glyph {
+            width: 2,
+            height: 2,
+            parts: vec![
+                GlyphPart::Char('█'),
+                GlyphPart::Char('█'),
+                GlyphPart::NewLine,
+                GlyphPart::Skip,
+                GlyphPart::Char('█'),
+            ],
+        },
+    );
+    Font::Tdf(font)
+}
+
+fn render(text: &str) -> Canvas {
+    Canvas::render_text(&block_font(), text, &RenderOptions::default()).unwrap()
+}
+
+#[test]
+fn solid_color_quantized() {
+    let mut canvas = render("A");
+    let mut colorizer = Colorizer::new(ColorFill::Solid(Rgb::new(0xF0, 0x50, 0x50)));
+    colorizer.true_color = false;
+    colorizer.apply(&mut canvas);
+    let cell = canvas.get(0, 0).unwrap();
+    assert_eq!(cell.fg, Some(12));
+    assert_eq!(cell.fg_rgb, None);
+    // Transparent cells stay transparent
+    assert!(canvas.get(0, 1).is_none());
+}
+
+#[test]
+fn horizontal_gradient_keeps_rgb() {
+    let mut canvas = render("AA");
+    Colorizer::new(ColorFill::Gradient {
+        direction: GradientDirection::Horizontal,
+        stops: vec![Rgb::new(0, 0, 0), Rgb::new(255, 255, 255)],
+    })
+    .apply(&mut canvas);
+    assert_eq!(canvas.get(0, 0).unwrap().fg_rgb, Some(Rgb::new(0, 0, 0)));
+    assert_eq!(canvas.get(1, 0).unwrap().fg_rgb, Some(Rgb::new(85, 85, 85)));
+    assert_eq!(
+        canvas.get(3, 0).unwrap().fg_rgb,
+        Some(Rgb::new(255, 255, 255))
+    );
+    assert_eq!(canvas.get(3, 0).unwrap().fg, Some(15));
+    assert!(to_ansi_string(&canvas, &Palette::dos()).contains("\x1B[38;2;85;85;85m"));
+}
+
+#[test]
+fn vertical_presets_and_rainbow() {
+    let mut canvas = render("A");
+    Colorizer::new(ColorFill::preset("fire").unwrap()).apply(&mut canvas);
+    assert_eq!(
+        canvas.get(0, 0).unwrap().fg_rgb,
+        Some(Rgb::new(0xFF, 0xFF, 0x55))
+    );
+    assert_eq!(
+        canvas.get(1, 1).unwrap().fg_rgb,
+        Some(Rgb::new(0xAA, 0x00, 0x00))
+    );
+
+    // One color per character
+    let mut canvas = render("AA");
+    Colorizer::new(ColorFill::Rainbow).apply(&mut canvas);
+    let fg = |x, y| canvas.get(x, y).unwrap().fg_rgb;
+    assert_eq!(fg(0, 0), Some(Rgb::new(255, 0, 0)));
+    assert_eq!(fg(1, 0), fg(0, 0));
+    assert_eq!(fg(1, 1), fg(0, 0));
+    assert_eq!(fg(2, 0), Some(Rgb::new(255, 128, 0)));
+    assert_eq!(fg(3, 1), fg(2, 0));
+
+    for name in ColorFill::PRESET_NAMES {
+        assert!(ColorFill::preset(name).is_some(), "{name}");
+    }
+}
+
+fn color_font() -> Font {
+    let mut font = TdfFont::new("COL", TdfFontType::Color, 0);
+    font.add_glyph(
+        'A',
+        Glyph {
+            width: 1,
+            height: 1,
+            parts: vec![GlyphPart::AnsiChar {
+                ch: 'x',
+                fg: 4,
+                bg: 1,
+                blink: false,
+            }],
+        },
+    );
+    Font::Tdf(font)
+}
+
+#[test]
+fn colored_cells_are_kept() {
+    let mut canvas = Canvas::render_text(&color_font(), "A", &RenderOptions::default()).unwrap();
+    Colorizer::new(ColorFill::Rainbow).apply(&mut canvas);
+    let cell = canvas.get(0, 0).unwrap();
+    assert_eq!(cell.fg, Some(4));
+    assert_eq!(cell.fg_rgb, None);
+}
+
+#[test]
+fn cell_colors_for_uncolored_fonts() {
+    let colors = CellColors {
+        fg: Some(Color::Index(14)),
+        bg: Some(Color::Rgb(Rgb::new(0x50, 0x50, 0xF0))),
+        ..Default::default()
+    };
+    let mut canvas = render("A");
+    colors.apply(&mut canvas, &Palette::dos());
+    let cell = canvas.get(0, 0).unwrap();
+    assert_eq!((cell.fg, cell.fg_rgb), (Some(14), None));
+    // Without iCE colors the background is matched against 0-7 only
+    assert_eq!(cell.bg, Some(1));
+    assert_eq!(cell.bg_rgb, Some(Rgb::new(0x50, 0x50, 0xF0)));
+
+    let mut canvas = render("A");
+    CellColors {
+        ice_colors: true,
+        ..colors.clone()
+    }
+    .apply(&mut canvas, &Palette::dos());
+    assert_eq!(canvas.get(0, 0).unwrap().bg, Some(9));
+
+    // Bright index backgrounds need iCE colors as well
+    let bright = CellColors {
+        bg: Some(Color::Index(12)),
+        ..Default::default()
+    };
+    let mut canvas = render("A");
+    bright.apply(&mut canvas, &Palette::dos());
+    assert_eq!(canvas.get(0, 0).unwrap().bg, Some(4));
+    let mut canvas = render("A");
+    CellColors {
+        ice_colors: true,
+        ..bright
+    }
+    .apply(&mut canvas, &Palette::dos());
+    assert_eq!(canvas.get(0, 0).unwrap().bg, Some(12));
+
+    // Color fonts keep their colors unless overridden
+    let mut canvas = Canvas::render_text(&color_font(), "A", &RenderOptions::default()).unwrap();
+    colors.apply(&mut canvas, &Palette::dos());
+    assert_eq!(canvas.get(0, 0).unwrap().fg, Some(4));
+    CellColors {
+        override_colored: true,
+        ..colors
+    }
+    .apply(&mut canvas, &Palette::dos());
+    assert_eq!(canvas.get(0, 0).unwrap().fg, Some(14));
+}
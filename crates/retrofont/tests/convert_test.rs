@@ -1,9 +1,30 @@
 use retrofont::{
-    convert::{can_convert_figlet_to_tdf, figlet_to_tdf},
-    figlet::FigletFont,
-    tdf::TdfFontType,
+    convert::{
+        bake_outline, can_convert_figlet_to_tdf, convert, figlet_to_outline_tdf, figlet_to_tdf,
+        fit_to_tdf, tdf_to_figlet, ConvertOptions, DropReason, DroppedGlyph, FigletExportOptions,
+        FontKind, GlyphFit, OutlineBakeOptions,
+    },
+    figlet::{FigletFont, FigletFormat, FigletLayout},
+    tdf::{TdfFont, TdfFontType},
+    Canvas, Font, FontError, Glyph, GlyphPart, RenderOptions,
 };
 
+fn glyph_lines(fig: &FigletFont, ch: char) -> Vec<String> {
+    let glyph = fig.glyph(ch).unwrap();
+    let mut lines = vec![String::new()];
+    for part in &glyph.parts {
+        match part {
+            GlyphPart::NewLine => lines.push(String::new()),
+            GlyphPart::HardBlank => lines.last_mut().unwrap().push(fig.hard_blank),
+            GlyphPart::Char(c) | GlyphPart::AnsiChar { ch: c, .. } => {
+                lines.last_mut().unwrap().push(*c)
+            }
+            other => panic!("unexpected part {other:?}"),
+        }
+    }
+    lines
+}
+
 #[test]
 fn test_figlet_to_tdf_compatibility() {
     // Load a FIGlet font
@@ -116,3 +137,475 @@ fn test_figlet_to_tdf_roundtrip() {
         "Character count should match after roundtrip"
     );
 }
+
+fn block_glyph(rows: &[&str]) -> Glyph {
+    let mut parts = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            parts.push(GlyphPart::NewLine);
+        }
+        parts.extend(row.chars().map(GlyphPart::Char));
+    }
+    Glyph {
+        width: rows.iter().map(|r| r.chars().count()).max().unwrap_or(0),
+        height: rows.len(),
+        parts,
+    }
+}
+
+#[test]
+fn test_tdf_to_figlet_block_normalizes_height() {
+    let mut tdf = TdfFont::new("Blocky", TdfFontType::Block, 2);
+    tdf.add_glyph('A', block_glyph(&["▄█▄", "█$█"]));
+    tdf.add_glyph('B', block_glyph(&["██", "█▄"]));
+    tdf.add_glyph('g', block_glyph(&["▄▄", "█", "▀"]));
+
+    let fig = tdf_to_figlet(&tdf, &FigletExportOptions::default()).unwrap();
+    // '$' is used by a glyph, so another hard blank is chosen
+    assert_ne!(fig.hard_blank, '$');
+    assert_eq!(fig.baseline, Some(2));
+    assert_eq!(glyph_lines(&fig, 'A'), vec!["▄█▄", "█$█", "   "]);
+    assert_eq!(glyph_lines(&fig, 'g'), vec!["▄▄", "█ ", "▀ "]);
+    let hb = fig.hard_blank.to_string().repeat(2);
+    assert_eq!(glyph_lines(&fig, ' '), vec![hb.as_str(); 3]);
+
+    // Round trip through the .flf writer and parser
+    let parsed = FigletFont::load(&fig.to_bytes().unwrap()).unwrap();
+    assert_eq!(parsed.hard_blank, fig.hard_blank);
+    assert_eq!(parsed.baseline, Some(2));
+    assert_eq!(glyph_lines(&parsed, 'B'), vec!["██", "█▄", "  "]);
+}
+
+#[test]
+fn test_tdf_to_figlet_hard_blank_is_ascii() {
+    let mut tdf = TdfFont::new("Busy", TdfFontType::Block, 1);
+    let candidates = "$#%&*+~^!?";
+    tdf.add_glyph('A', block_glyph(&[candidates, "¡¢£"]));
+    let fig = tdf_to_figlet(&tdf, &FigletExportOptions::default()).unwrap();
+    assert!(fig.hard_blank.is_ascii_graphic());
+    assert!(!candidates.contains(fig.hard_blank));
+    assert!(fig.to_bytes().unwrap().starts_with(b"flf2a"));
+
+    let all: String = ('!'..='~').collect();
+    tdf.add_glyph('B', block_glyph(&[all.as_str()]));
+    assert!(matches!(
+        tdf_to_figlet(&tdf, &FigletExportOptions::default()),
+        Err(FontError::NoHardBlank)
+    ));
+}
+
+#[test]
+fn test_tdf_to_figlet_bakes_outline_style() {
+    let mut tdf = TdfFont::new("Outl", TdfFontType::Outline, 1);
+    tdf.add_glyph(
+        'O',
+        Glyph {
+            width: 3,
+            height: 1,
+            parts: vec![
+                GlyphPart::OutlinePlaceholder(b'E'),
+                GlyphPart::FillMarker,
+                GlyphPart::OutlinePlaceholder(b'F'),
+            ],
+        },
+    );
+    let fig = tdf_to_figlet(&tdf, &FigletExportOptions::default()).unwrap();
+    assert_eq!(glyph_lines(&fig, 'O'), vec!["┌ ┐"]);
+}
+
+#[test]
+fn test_tdf_to_figlet_colors() {
+    let fonts = TdfFont::load(include_bytes!("tdf/CODERX.TDF")).unwrap();
+    let plain = tdf_to_figlet(&fonts[0], &FigletExportOptions::default()).unwrap();
+    let bytes = plain.to_bytes().unwrap();
+    assert!(bytes.starts_with(b"flf2a"));
+    assert!(!bytes.contains(&0x1B));
+
+    let colored = tdf_to_figlet(
+        &fonts[0],
+        &FigletExportOptions {
+            keep_colors: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let bytes = colored.to_bytes().unwrap();
+    assert!(bytes.starts_with(b"tlf2a"));
+    assert!(bytes.windows(2).any(|w| w == b"\x1B["));
+    assert_eq!(glyph_lines(&colored, 'A'), glyph_lines(&plain, 'A'));
+}
+
+fn parts_to_string(glyph: &Glyph) -> String {
+    glyph
+        .parts
+        .iter()
+        .map(|p| match p {
+            GlyphPart::NewLine => '\n',
+            GlyphPart::FillMarker => '@',
+            GlyphPart::OutlineHole => 'O',
+            GlyphPart::OutlinePlaceholder(b) => *b as char,
+            GlyphPart::Skip => ' ',
+            GlyphPart::Char(c) => *c,
+            other => panic!("unexpected part {other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_figlet_to_outline_traces_strokes() {
+    let mut fig = FigletFont::new("Thick");
+    fig.add_raw_char(b'O', &["######", "######", "##  ##", "######", "######"]);
+    fig.add_raw_char(b'-', &["    ", "####", "####", "    "]);
+    fig.add_raw_char(b'I', &["#", "#"]);
+
+    let conversion = figlet_to_outline_tdf(&fig).unwrap();
+    let font = &conversion.font;
+    assert_eq!(font.font_type, TdfFontType::Outline);
+    assert_eq!(
+        parts_to_string(font.glyph('O').unwrap()),
+        "EAAAAF\nCGBBHD\nCDOOCD\nCIAAJD\nKBBBBL"
+    );
+    assert_eq!(
+        parts_to_string(font.glyph('-').unwrap()),
+        "    \nEAAF\nKBBL\n    "
+    );
+
+    // One cell wide strokes cannot be outlined and are drawn with full blocks
+    assert_eq!(conversion.block_fallback, vec!['I']);
+    assert_eq!(parts_to_string(font.glyph('I').unwrap()), "█\n█");
+
+    // figlet_to_tdf delegates for Outline
+    let tdf = figlet_to_tdf(&fig, TdfFontType::Outline).unwrap();
+    assert_eq!(tdf.glyph_count(), 3);
+}
+
+#[test]
+fn test_figlet_to_outline_round_trip() {
+    let mut fig = FigletFont::new("Thick");
+    fig.add_raw_char(b'O', &["######", "######", "##  ##", "######", "######"]);
+    let tdf = figlet_to_outline_tdf(&fig).unwrap().font;
+    let parsed = TdfFont::load(&tdf.to_bytes().unwrap()).unwrap();
+    assert_eq!(
+        parts_to_string(parsed[0].glyph('O').unwrap()),
+        parts_to_string(tdf.glyph('O').unwrap())
+    );
+}
+
+#[test]
+fn test_figlet_to_outline_fallback_round_trip() {
+    // Thin glyphs drawn with characters that are outline codes in TDF files
+    let mut fig = FigletFont::new("Thin");
+    fig.add_raw_char(b'A', &[" A ", "O@O", "& B"]);
+    fig.add_raw_char(b'/', &["  /", " / ", "/  "]);
+    let conversion = figlet_to_outline_tdf(&fig).unwrap();
+    assert_eq!(conversion.block_fallback, vec!['/', 'A']);
+
+    let parsed = TdfFont::load(&conversion.font.to_bytes().unwrap()).unwrap();
+    for ch in ['A', '/'] {
+        let glyph = parsed[0].glyph(ch).unwrap();
+        assert_eq!(glyph.parts, conversion.font.glyph(ch).unwrap().parts);
+        assert!(glyph.parts.iter().all(|p| matches!(
+            p,
+            GlyphPart::Char('█') | GlyphPart::Skip | GlyphPart::NewLine
+        )));
+    }
+    assert_eq!(
+        parts_to_string(parsed[0].glyph('A').unwrap()),
+        " █ \n███\n█ █"
+    );
+}
+
+fn canvas_cells(canvas: &Canvas) -> Vec<Vec<Option<char>>> {
+    canvas
+        .lines()
+        .iter()
+        .map(|l| l.iter().map(|c| c.map(|c| c.ch)).collect())
+        .collect()
+}
+
+#[test]
+fn test_bake_outline_matches_render() {
+    let mut fig = FigletFont::new("Thick");
+    fig.add_raw_char(b'O', &["######", "######", "##  ##", "######", "######"]);
+    let outline = figlet_to_outline_tdf(&fig).unwrap().font;
+
+    for style in [0, 5, 18] {
+        let options = RenderOptions::default().outline_style(style);
+        let expected = Canvas::render_text(&Font::Tdf(outline.clone()), "O", &options).unwrap();
+        let baked = bake_outline(
+            &outline,
+            &OutlineBakeOptions {
+                outline_style: style,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(baked.font_type, TdfFontType::Block);
+        // Fills and holes stay opaque, also after saving and loading
+        let reloaded = TdfFont::load(&baked.to_bytes().unwrap()).unwrap().remove(0);
+        for font in [baked, reloaded] {
+            let actual = Canvas::render_text(&Font::Tdf(font), "O", &options).unwrap();
+            assert_eq!(
+                canvas_cells(&actual),
+                canvas_cells(&expected),
+                "style {style}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_bake_outline_color() {
+    let mut fig = FigletFont::new("Thick");
+    fig.add_raw_char(b'-', &["####", "####"]);
+    let outline = figlet_to_outline_tdf(&fig).unwrap().font;
+    let baked = bake_outline(
+        &outline,
+        &OutlineBakeOptions {
+            target_type: TdfFontType::Color,
+            outline_fg: 14,
+            outline_bg: 1,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let parsed = TdfFont::load(&baked.to_bytes().unwrap()).unwrap();
+    assert_eq!(parsed[0].font_type, TdfFontType::Color);
+    assert_eq!(
+        parsed[0].glyph('-').unwrap().parts[0],
+        GlyphPart::AnsiChar {
+            ch: '┌',
+            fg: 14,
+            bg: 1,
+            blink: false
+        }
+    );
+
+    // Only outline fonts can be baked, and only into Block or Color
+    assert!(bake_outline(&parsed[0], &OutlineBakeOptions::default()).is_err());
+    assert!(bake_outline(
+        &outline,
+        &OutlineBakeOptions {
+            target_type: TdfFontType::Outline,
+            ..Default::default()
+        }
+    )
+    .is_err());
+}
+
+#[test]
+fn test_convert_reports_losses() {
+    let mut fig = FigletFont::new("A very long font name");
+    fig.add_raw_char(b'A', &["/\\", "€€"]);
+    fig.add_raw_char(b'B', &[&"#".repeat(31)]);
+    fig.add_raw_char(0xC4, &["-"]);
+    let font = Font::Figlet(fig);
+
+    let conversion = convert(&font, FontKind::TdfBlock, &ConvertOptions::default()).unwrap();
+    let report = &conversion.report;
+    assert_eq!(
+        report.dropped,
+        vec![
+            DroppedGlyph {
+                ch: 'B',
+                reason: DropReason::TooLarge {
+                    width: 31,
+                    height: 1
+                }
+            },
+            DroppedGlyph {
+                ch: 'Ä',
+                reason: DropReason::OutOfRange
+            },
+        ]
+    );
+    assert_eq!(report.unmappable, vec!['€']);
+    assert_eq!(
+        report.truncated_name.as_deref(),
+        Some("A very long font name")
+    );
+    assert!(!report.is_lossless());
+    assert!(report.to_string().contains("no CP437 equivalent"));
+
+    let Font::Tdf(tdf) = &conversion.font else {
+        panic!("expected a TDF font");
+    };
+    assert_eq!(tdf.name, "A very long ");
+    assert_eq!(tdf.font_type, TdfFontType::Block);
+    assert_eq!(FontKind::of(&conversion.font), FontKind::TdfBlock);
+
+    // Same kind is a lossless copy
+    let copy = convert(&font, FontKind::Figlet, &ConvertOptions::default()).unwrap();
+    assert!(copy.report.is_lossless());
+}
+
+#[test]
+fn test_convert_color_font_matrix() {
+    let fonts = TdfFont::load(include_bytes!("tdf/CODERX.TDF")).unwrap();
+    let color = Font::Tdf(fonts[0].clone());
+    let options = ConvertOptions::default();
+
+    // TOIlet keeps the colors and loads back as a colored FIGlet font
+    let tlf = convert(&color, FontKind::Tlf, &options).unwrap();
+    assert!(tlf.report.is_lossless());
+    assert_eq!(tlf.font.default_extension(), "tlf");
+    let loaded = Font::load(&tlf.font.to_bytes().unwrap()).unwrap();
+    let Font::Figlet(fig) = &loaded[0] else {
+        panic!("expected a FIGlet font");
+    };
+    assert_eq!(fig.format, FigletFormat::Tlf);
+    assert_eq!(FontKind::of(&loaded[0]), FontKind::Tlf);
+
+    // Plain FIGlet and Block drop the colors
+    let flf = convert(&color, FontKind::Figlet, &options).unwrap();
+    assert!(flf.report.lost_colors.contains(&'A'));
+    assert!(flf.font.to_bytes().unwrap().starts_with(b"flf2a"));
+    let block = convert(&color, FontKind::TdfBlock, &options).unwrap();
+    assert!(block.report.lost_colors.contains(&'A'));
+    let Font::Tdf(block) = &block.font else {
+        panic!("expected a TDF font");
+    };
+    assert!(block
+        .glyph('A')
+        .unwrap()
+        .parts
+        .iter()
+        .all(|p| !matches!(p, GlyphPart::AnsiChar { .. })));
+
+    // Block to Color adds default colors
+    let recolored = convert(&Font::Tdf(block.clone()), FontKind::TdfColor, &options).unwrap();
+    assert!(recolored.report.is_lossless());
+    assert_eq!(FontKind::of(&recolored.font), FontKind::TdfColor);
+
+    // Color to Outline traces the glyph shapes
+    let outline = convert(&color, FontKind::TdfOutline, &options).unwrap();
+    assert_eq!(FontKind::of(&outline.font), FontKind::TdfOutline);
+    assert!(outline.report.lost_colors.contains(&'A'));
+}
+
+fn tall_font() -> FigletFont {
+    // 16 rows with a blank top and bottom row, 'W' is also too wide
+    let mut rows = vec![" ".repeat(4)];
+    rows.extend(std::iter::repeat_n("##  ".to_string(), 14));
+    rows.push(" ".repeat(4));
+    let mut fig = FigletFont::new("Tall");
+    fig.add_raw_char(b'I', &rows.iter().map(String::as_str).collect::<Vec<_>>());
+    let wide: Vec<String> = rows
+        .iter()
+        .map(|r| r.replace("##", "# ").repeat(9))
+        .collect();
+    fig.add_raw_char(b'W', &wide.iter().map(String::as_str).collect::<Vec<_>>());
+    fig
+}
+
+#[test]
+fn test_fit_modes() {
+    let fig = tall_font();
+    let font = Font::Figlet(fig.clone());
+
+    // Dropping is the default
+    let dropped = convert(&font, FontKind::TdfBlock, &ConvertOptions::default());
+    assert!(dropped.is_err());
+
+    let crop = fit_to_tdf(&fig, GlyphFit::Crop);
+    assert_eq!(crop.fonts.len(), 1);
+    let i = crop.fonts[0].glyph('I').unwrap();
+    assert_eq!((i.width, i.height), (4, 12));
+    let w = crop.fonts[0].glyph('W').unwrap();
+    assert_eq!((w.width, w.height), (30, 12));
+    assert_eq!(
+        crop.clipped.iter().map(|c| c.ch).collect::<Vec<_>>(),
+        vec!['I', 'W']
+    );
+
+    // The whole font is halved in both directions since 'W' is 33 columns wide after trimming
+    let conversion = convert(
+        &font,
+        FontKind::TdfBlock,
+        &ConvertOptions {
+            fit: GlyphFit::Downscale,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let Font::Tdf(tdf) = &conversion.font else {
+        panic!("expected a TDF font");
+    };
+    assert_eq!(
+        parts_to_string(tdf.glyph('I').unwrap()),
+        "█\n".repeat(6) + "█"
+    );
+    let w = tdf.glyph('W').unwrap();
+    assert_eq!((w.width, w.height), (17, 7));
+    assert!(parts_to_string(w).starts_with(&("▌ ".repeat(8) + "▌\n")));
+    assert_eq!(conversion.report.clipped.len(), 2);
+    assert!(conversion.report.dropped.is_empty());
+
+    // Splitting keeps all rows, 'W' is still too wide
+    let conversion = convert(
+        &font,
+        FontKind::TdfBlock,
+        &ConvertOptions {
+            fit: GlyphFit::Split,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(conversion.font.name(), "Tall 1");
+    assert_eq!(conversion.split_fonts.len(), 1);
+    assert_eq!(conversion.split_fonts[0].name(), "Tall 2");
+    assert_eq!(conversion.font.glyph_size('I'), Some((2, 12)));
+    assert_eq!(conversion.split_fonts[0].glyph_size('I'), Some((2, 2)));
+    assert_eq!(
+        conversion.report.dropped,
+        vec![DroppedGlyph {
+            ch: 'W',
+            reason: DropReason::TooLarge {
+                width: 36,
+                height: 16
+            }
+        }]
+    );
+}
+
+#[test]
+fn test_figlet_to_tdf_transparency_and_spacing() {
+    let mut fig = FigletFont::new("Gaps");
+    fig.add_raw_char(b'O', &[" ## $  ", "#  #   "]);
+    fig.add_raw_char(b'I', &["#  ", "#  "]);
+
+    let tdf = figlet_to_tdf(&fig, TdfFontType::Color).unwrap();
+    // Both glyphs have two trailing blank columns in a full width font
+    assert_eq!(tdf.spacing, 2);
+    let o = tdf.glyph('O').unwrap();
+    assert_eq!((o.width, o.height), (5, 2));
+    let visible = |ch| GlyphPart::AnsiChar {
+        ch,
+        fg: 7,
+        bg: 0,
+        blink: false,
+    };
+    assert_eq!(
+        o.parts,
+        vec![
+            GlyphPart::Skip,
+            visible('#'),
+            visible('#'),
+            visible(' '),
+            GlyphPart::HardBlank,
+            GlyphPart::NewLine,
+            visible('#'),
+            visible(' '),
+            visible(' '),
+            visible('#'),
+            GlyphPart::Skip,
+        ]
+    );
+
+    // Kerning and smushing fonts move glyphs together
+    fig.header = "flf2a$ 2 2 7 0 0".into();
+    assert_eq!(fig.layout(), FigletLayout::Kerning);
+    assert_eq!(figlet_to_tdf(&fig, TdfFontType::Block).unwrap().spacing, 0);
+    fig.header = "flf2a$ 2 2 7 -1 0 0 191".into();
+    assert_eq!(fig.layout(), FigletLayout::Smushing);
+}
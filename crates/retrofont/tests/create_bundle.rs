@@ -75,8 +75,11 @@ fn inspect_bundle_creation() {
     );
 
     let bundle = TdfFont::serialize_bundle(&[f1, f2, f3]).unwrap();
-    std::fs::write("test_bundle.tdf", &bundle).unwrap();
+    let path = std::env::temp_dir().join("test_bundle.tdf");
+    std::fs::write(&path, &bundle).unwrap();
     eprintln!(
-        "Created test_bundle.tdf - run: cargo run --release -- inspect --font test_bundle.tdf"
+        "Created {} - run: cargo run --release -- inspect --font {}",
+        path.display(),
+        path.display()
     );
 }
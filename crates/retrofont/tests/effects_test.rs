@@ -0,0 +1,75 @@
+use retrofont::{
+    effects::{Border, Extrusion, Shadow},
+    Canvas, Cell, Color, Palette,
+};
+
+/// `.` is transparent, everything else is drawn.
+fn canvas(rows: &[&str]) -> Canvas {
+    let mut canvas = Canvas::new();
+    for (y, row) in rows.iter().enumerate() {
+        for (x, ch) in row.chars().enumerate() {
+            if ch != '.' {
+                canvas.set(x, y, Some(Cell::new(ch, None, None, false)));
+            }
+        }
+    }
+    canvas
+}
+
+fn text(canvas: &Canvas) -> Vec<String> {
+    canvas
+        .lines()
+        .iter()
+        .map(|line| {
+            let mut row: String = line.iter().map(|c| c.map_or('.', |c| c.ch)).collect();
+            row.extend(std::iter::repeat_n('.', canvas.width() - line.len()));
+            row
+        })
+        .collect()
+}
+
+#[test]
+fn shadow_follows_shape() {
+    let mut c = canvas(&["#.#", "# #"]);
+    Shadow {
+        offset: (1, 1),
+        color: Some(Color::Index(8)),
+        ..Shadow::default()
+    }
+    .apply(&mut c, &Palette::default());
+    // The space is no ink, so it casts no shadow but can receive one.
+    assert_eq!(text(&c), ["#.#.", "#░#░", ".░.░"]);
+    assert_eq!(c.get(1, 1).unwrap().fg, Some(8));
+    assert_eq!(c.get(0, 0).unwrap().fg, None);
+}
+
+#[test]
+fn border_traces_outline() {
+    let mut c = canvas(&["##", "##"]);
+    Border::default().apply(&mut c, &Palette::default());
+    assert_eq!(text(&c), ["┌──┐", "│##│", "│##│", "└──┘"]);
+
+    // Style 1 has double top edges; the concave corner of an L joins a top edge.
+    let mut c = canvas(&["#..", "###"]);
+    Border {
+        style: 1,
+        color: None,
+    }
+    .apply(&mut c, &Palette::default());
+    assert_eq!(text(&c), ["╒═╕..", "│#╘═╕", "│###│", "└───┘"]);
+}
+
+#[test]
+fn extrusion_layers_back_to_front() {
+    let mut c = canvas(&["#"]);
+    Extrusion {
+        depth: 3,
+        step: (1, 0),
+        ch: '█',
+        colors: vec![Color::Index(7), Color::Index(8)],
+    }
+    .apply(&mut c, &Palette::default());
+    assert_eq!(text(&c), ["#███"]);
+    let colors: Vec<_> = (1..4).map(|x| c.get(x, 0).unwrap().fg).collect();
+    assert_eq!(colors, [Some(7), Some(8), Some(8)]);
+}
@@ -1,4 +1,7 @@
-use retrofont::{figlet::FigletFont, test_support::MemoryBufferTarget, Font, RenderOptions};
+use retrofont::{
+    figlet::FigletFont, test_support::MemoryBufferTarget, validate::Issue, Font, FontError,
+    RenderOptions,
+};
 
 fn lines(buf: &MemoryBufferTarget) -> Vec<String> {
     buf.lines
@@ -43,3 +46,67 @@ fn figlet_edit_equals_display() {
         .unwrap();
     assert_eq!(lines(&d), lines(&e));
 }
+
+#[test]
+fn toilet_colors_are_parsed() {
+    let data = b"tlf2a$ 1 1 4 0 0\n\x1B[0;1;31mA\x1B[0mb@@\n";
+    let font = FigletFont::load(data).unwrap();
+    assert!(font.is_colored());
+    let glyph = font.glyph(' ').unwrap();
+    assert_eq!(
+        glyph.parts[0],
+        retrofont::GlyphPart::AnsiChar {
+            ch: 'A',
+            fg: 12,
+            bg: 0,
+            blink: false
+        }
+    );
+    assert_eq!(glyph.parts[1], retrofont::GlyphPart::Char('b'));
+}
+
+#[test]
+fn figlet_errors_name_line_and_file() {
+    // A missing end mark stops loading, the characters before it are kept
+    let data = b"flf2a$ 2 2 10 0 1\ncomment\n a@\n b@@\nc\nd@@\n";
+    let font = FigletFont::load(data).unwrap();
+    assert!(font.has_char(' '));
+    assert!(!font.has_char('!'));
+    let err = font.parse_error().unwrap();
+    assert!(matches!(
+        err,
+        FontError::FigletMissingMarker { line: 5, ch: '!' }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "FIGlet: line 5 of '!' is missing the @ marker"
+    );
+    let diagnostics = font.validate();
+    assert_eq!(diagnostics[0].issue, Issue::MissingEndMark { line: 5 });
+    assert_eq!(diagnostics[0].ch, Some('!'));
+    assert_eq!(diagnostics[0].offset, Some(data.len() - 6));
+
+    // Other end mark characters are not supported, but the font still loads
+    let font = FigletFont::load(b"flf2a$ 1 1 10 0 0\n a#\n").unwrap();
+    assert_eq!(font.glyph_count(), 0);
+    assert!(font.parse_error().is_some());
+
+    let path = std::env::temp_dir().join("retrofont_bad_utf8.flf");
+    std::fs::write(&path, b"flf2a$ 1 1 10 0 0\n\xFF@\n").unwrap();
+    let err = FigletFont::load_file(&path).err().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        err.root(),
+        FontError::FigletInvalidUtf8 {
+            line: 2,
+            offset: 18
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{}: FIGlet: invalid UTF-8 at line 2 (byte 18)",
+            path.display()
+        )
+    );
+}
@@ -0,0 +1,72 @@
+use retrofont::{test_support::glyph, Glyph, GlyphGrid, GlyphPart};
+
+fn text(grid: &GlyphGrid) -> Vec<String> {
+    grid.rows()
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|p| match p {
+                    GlyphPart::Skip => '.',
+                    GlyphPart::Char(c) => *c,
+                    _ => '?',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn grid_round_trip_recomputes_size() {
+    let source = glyph(&["AB", "C", "DEF"]);
+    let grid = GlyphGrid::from(&source);
+    assert_eq!((grid.width(), grid.height()), (3, 3));
+    assert_eq!(text(&grid), ["AB.", "C..", "DEF"]);
+    assert_eq!(grid.get(2, 2), Some(&GlyphPart::Char('F')));
+    assert_eq!(grid.get(3, 0), None);
+
+    let back = grid.to_glyph();
+    assert_eq!((back.width, back.height), (3, 3));
+    assert_eq!(GlyphGrid::from(&back), grid);
+
+    // End markers keep their column.
+    let outline = glyph(&["AB&", "C&"]);
+    let back: Glyph = GlyphGrid::from(&outline).into();
+    assert_eq!(back.parts, glyph(&["AB&", "C&."]).parts);
+}
+
+#[test]
+fn grid_set_grows() {
+    let mut grid = GlyphGrid::new(2, 1);
+    grid.set(0, 0, GlyphPart::Char('A'));
+    grid.set(3, 2, GlyphPart::Char('Z'));
+    assert_eq!(text(&grid), ["A...", "....", "...Z"]);
+}
+
+#[test]
+fn grid_rows_and_columns() {
+    let mut grid = GlyphGrid::from(&glyph(&["AB", "CD"]));
+    grid.insert_row(1);
+    grid.insert_column(0);
+    assert_eq!(text(&grid), [".AB", "...", ".CD"]);
+    assert_eq!(
+        grid.delete_column(1),
+        [GlyphPart::Char('A'), GlyphPart::Skip, GlyphPart::Char('C')]
+    );
+    assert_eq!(grid.delete_row(0), [GlyphPart::Skip, GlyphPart::Char('B')]);
+    assert_eq!(text(&grid), ["..", ".D"]);
+    assert_eq!(grid.to_glyph().width, 2);
+
+    grid.resize(1, 3);
+    assert_eq!(text(&grid), [".", ".", "."]);
+}
+
+#[test]
+fn grid_crop_to_ink() {
+    let mut grid = GlyphGrid::from(&glyph(&["....", ".A  ", "..B ", "...."]));
+    assert_eq!(grid.crop_to_ink(), (1, 1));
+    assert_eq!(text(&grid), ["A ", ".B"]);
+
+    let mut empty = GlyphGrid::from(&glyph(&["  ", ".."]));
+    empty.crop_to_ink();
+    assert_eq!((empty.width(), empty.height()), (0, 0));
+}
@@ -0,0 +1,52 @@
+use retrofont::{
+    figlet::FigletFont,
+    metrics::InkBounds,
+    tdf::{TdfFont, TdfFontType},
+    test_support::glyph,
+    Font,
+};
+
+#[test]
+fn ink_bounds_ignore_blank_cells() {
+    let bounds = InkBounds::of(&glyph(&["....", ". X ", " XX.", "    "])).unwrap();
+    assert_eq!(
+        bounds,
+        InkBounds {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 2
+        }
+    );
+    assert_eq!((bounds.right(), bounds.bottom()), (3, 3));
+    assert_eq!(InkBounds::of(&glyph(&[" .", ". "])), None);
+}
+
+#[test]
+fn figlet_metrics() {
+    let doom = Font::Figlet(FigletFont::load(include_bytes!("figlet/doom.flf")).unwrap());
+    let metrics = doom.metrics();
+    assert_eq!(metrics.height, 8);
+    assert_eq!(metrics.baseline, 6);
+    assert_eq!(metrics.cap_height, 6);
+    assert_eq!(metrics.x_height, 4);
+    assert_eq!(metrics.descender, 2);
+    assert!(metrics.average_advance > 1.0);
+    assert!(metrics.max_advance as f32 >= metrics.average_advance);
+}
+
+#[test]
+fn tdf_metrics() {
+    let mut font = TdfFont::new("MIXED", TdfFontType::Block, 0);
+    font.add_glyph('A', glyph(&["AAA", "A.A", "A.A"]));
+    font.add_glyph('x', glyph(&["x.x", ".x."]));
+    font.add_glyph('p', glyph(&["pp", "pp", "p."]));
+    font.add_glyph('-', glyph(&["  ", "--", "  "]));
+    let metrics = Font::Tdf(font).metrics();
+    assert_eq!(metrics.baseline, 3);
+    assert_eq!(metrics.cap_height, 3);
+    assert_eq!(metrics.x_height, 2);
+    assert_eq!(metrics.descender, 1);
+    assert_eq!(metrics.max_advance, 3);
+    assert_eq!(metrics.average_advance, 2.5);
+}
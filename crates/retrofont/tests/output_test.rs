@@ -0,0 +1,174 @@
+use retrofont::{
+    output::{
+        encode, render_canvas, to_ansi_string, to_bbs_bytes, BbsDialect, Encoding, OutputFormat,
+        OutputOptions,
+    },
+    tdf::{TdfFont, TdfFontType},
+    Canvas, Cell, Font, FontTarget, Glyph, GlyphPart, Palette, RenderOptions,
+};
+
+fn color_font() -> Font {
+    let mut font = TdfFont::new("BBS", TdfFontType::Color, 0);
+    font.add_glyph(
+        'A',
+        Glyph {
+            width: 2,
+            height: 2,
+            parts: vec![
+                GlyphPart::AnsiChar {
+                    ch: '█',
+                    fg: 0xF,
+                    bg: 0x1,
+                    blink: false,
+                },
+                GlyphPart::AnsiChar {
+                    ch: '▄',
+                    fg: 0xF,
+                    bg: 0x1,
+                    blink: false,
+                },
+                GlyphPart::NewLine,
+                GlyphPart::Skip,
+                GlyphPart::AnsiChar {
+                    ch: 'x',
+                    fg: 0x4,
+                    bg: 0x0,
+                    blink: true,
+                },
+            ],
+        },
+    );
+    Font::Tdf(font)
+}
+
+fn render(dialect: BbsDialect) -> Vec<u8> {
+    let canvas = Canvas::render_text(&color_font(), "A", &RenderOptions::default()).unwrap();
+    to_bbs_bytes(&canvas, dialect)
+}
+
+#[test]
+fn canvas_lays_out_glyphs_horizontally() {
+    let canvas = Canvas::render_text(&color_font(), "AA", &RenderOptions::default()).unwrap();
+    assert_eq!(canvas.width(), 4);
+    assert_eq!(canvas.height(), 2);
+    assert_eq!(canvas.get(2, 0).map(|c| c.ch), Some('█'));
+    // Skipped cells stay transparent
+    assert!(canvas.get(0, 1).is_none());
+    assert!(canvas.get(2, 1).is_none());
+}
+
+#[test]
+fn default_canvas_matches_new() {
+    assert_eq!(Canvas::default().height(), Canvas::new().height());
+    let mut canvas = Canvas::default();
+    canvas.draw(Cell::new('x', None, None, false)).unwrap();
+    assert_eq!(canvas.height(), 1);
+}
+
+#[test]
+fn pcboard_codes() {
+    assert_eq!(
+        render(BbsDialect::PcBoard),
+        b"@X1F\xDB\xDC\r\n@X07 @X84x@X07\r\n".to_vec()
+    );
+}
+
+#[test]
+fn pipe_codes() {
+    assert_eq!(
+        render(BbsDialect::Pipe),
+        b"|15|17\xDB\xDC\r\n|07|16 |04|24x|07|16\r\n".to_vec()
+    );
+}
+
+#[test]
+fn ctrl_a_codes() {
+    assert_eq!(
+        render(BbsDialect::CtrlA),
+        b"\x01N\x01H\x01W\x014\xDB\xDC\r\n\x01N\x01W\x010 \x01N\x01R\x010\x01Ix\x01N\x01W\x010\r\n"
+            .to_vec()
+    );
+}
+
+#[test]
+fn avatar_codes() {
+    assert_eq!(
+        render(BbsDialect::Avatar),
+        b"\x16\x01\x1F\xDB\xDC\r\n\x16\x01\x07 \x16\x01\x04\x16\x02x\x16\x01\x07\r\n".to_vec()
+    );
+}
+
+#[test]
+fn cp437_encoding_passes_control_codes() {
+    let out = encode("\x1B[5m█▄░\r\n", Encoding::Cp437);
+    assert_eq!(out.bytes, b"\x1B[5m\xDB\xDC\xB0\r\n".to_vec());
+    assert!(out.unencodable.is_empty());
+}
+
+#[test]
+fn ansi_resets_when_colors_are_dropped() {
+    let mut canvas = Canvas::new();
+    canvas.draw(Cell::new('A', Some(4), Some(1), true)).unwrap();
+    canvas.draw(Cell::new('b', None, None, false)).unwrap();
+    canvas.skip().unwrap();
+    canvas.draw(Cell::new('C', Some(4), None, false)).unwrap();
+    canvas.draw(Cell::new('D', Some(4), None, false)).unwrap();
+    assert_eq!(
+        to_ansi_string(&canvas, &Palette::dos()),
+        "\x1B[5m\x1B[38;2;170;0;0m\x1B[48;2;0;0;170mA\x1B[0mb \x1B[38;2;170;0;0mCD\x1B[0m"
+    );
+
+    // Uncolored lines need no codes
+    let mut canvas = Canvas::new();
+    canvas.draw(Cell::new('x', None, None, false)).unwrap();
+    assert_eq!(to_ansi_string(&canvas, &Palette::dos()), "x");
+}
+
+#[test]
+fn cp437_encoding_reports_unencodable() {
+    let out = encode("▛A▛╭", Encoding::Cp437);
+    assert_eq!(out.bytes, b"?A??".to_vec());
+    assert_eq!(out.unencodable, vec!['▛', '╭']);
+}
+
+#[test]
+fn encoding_independent_of_color_backend() {
+    let canvas = Canvas::render_text(&color_font(), "A", &RenderOptions::default()).unwrap();
+
+    let ansi = render_canvas(
+        &canvas,
+        &OutputOptions {
+            encoding: Encoding::Cp437,
+            ..OutputOptions::new(OutputFormat::Ansi)
+        },
+    );
+    assert!(ansi
+        .bytes
+        .starts_with(b"\x1B[38;2;255;255;255m\x1B[48;2;0;0;170m\xDB"));
+
+    let pipe = render_canvas(
+        &canvas,
+        &OutputOptions {
+            encoding: Encoding::Utf8,
+            ..OutputOptions::new(OutputFormat::Bbs(BbsDialect::Pipe))
+        },
+    );
+    assert!(String::from_utf8(pipe.bytes)
+        .unwrap()
+        .starts_with("|15|17█▄\r\n"));
+}
+
+#[test]
+fn ice_backgrounds_in_bbs_dialects() {
+    let mut canvas = Canvas::new();
+    canvas.draw(Cell::from_attribute('X', 0xE1, true)).unwrap();
+    assert_eq!(
+        to_bbs_bytes(&canvas, BbsDialect::PcBoard),
+        b"@XE1X@X07\r\n".to_vec()
+    );
+    assert_eq!(
+        to_bbs_bytes(&canvas, BbsDialect::Pipe),
+        b"|01|30X|07|16\r\n".to_vec()
+    );
+    assert!(to_bbs_bytes(&canvas, BbsDialect::CtrlA).starts_with(b"\x01N\x01B\x013\x01EX"));
+}
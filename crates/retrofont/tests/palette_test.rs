@@ -0,0 +1,133 @@
+use retrofont::{
+    output::{render_canvas, to_ansi_string, OutputFormat, OutputOptions},
+    tdf::{TdfFont, TdfFontType},
+    Canvas, Color, Font, FontError, Glyph, GlyphPart, Palette, RenderOptions, Rgb,
+};
+
+fn red_font() -> Font {
+    let mut font = TdfFont::new("RED", TdfFontType::Color, 0);
+    font.add_glyph(
+        'A',
+        Glyph {
+            width: 1,
+            height: 1,
+            parts: vec![GlyphPart::AnsiChar {
+                ch: '█',
+                fg: 4,
+                bg: 12,
+                blink: false,
+            }],
+        },
+    );
+    Font::Tdf(font)
+}
+
+#[test]
+fn builtin_palettes_by_name() {
+    for name in Palette::BUILTIN_NAMES {
+        assert!(Palette::by_name(name).is_some(), "{name}");
+    }
+    assert_eq!(Palette::by_name("VGA"), Some(Palette::dos()));
+    assert!(Palette::by_name("zx").is_none());
+    assert_eq!(Palette::dos().rgb(6), Rgb::new(0xAA, 0x55, 0x00));
+    assert_eq!(Palette::cga().rgb(6), Rgb::new(0xAA, 0xAA, 0x00));
+}
+
+#[test]
+fn ega_palette_registers() {
+    assert_eq!(Palette::ega_color(20), Rgb::new(0xAA, 0x55, 0x00));
+    assert_eq!(Palette::ega_color(6), Rgb::new(0xAA, 0xAA, 0x00));
+    assert_eq!(Palette::ega_color(63), Rgb::new(0xFF, 0xFF, 0xFF));
+    // The power-on registers select the DOS colors
+    assert_eq!(Palette::ega().colors, Palette::dos().colors);
+
+    let mut registers = Palette::EGA_DEFAULT_REGISTERS;
+    registers[6] = 6;
+    let palette = Palette::ega_registers(registers).unwrap();
+    assert_eq!(palette.rgb(6), Rgb::new(0xAA, 0xAA, 0x00));
+    assert_eq!(
+        Palette::by_name("ega:0,1,2,3,4,5,6,7,56,57,58,59,60,61,62,63"),
+        Some(palette)
+    );
+
+    registers[0] = 64;
+    assert!(matches!(
+        Palette::ega_registers(registers),
+        Err(FontError::InvalidPalette(_))
+    ));
+    assert!(Palette::by_name("ega:0,1,2").is_none());
+}
+
+#[test]
+fn parse_hex_and_gimp_palettes() {
+    let hex: String = (0..16).map(|i| format!("#{:02X}0000\n", i * 16)).collect();
+    let palette = Palette::parse("reds", &hex).unwrap();
+    assert_eq!(palette.rgb(15), Rgb::new(0xF0, 0, 0));
+
+    let mut gpl = String::from("GIMP Palette\nName: Test\nColumns: 4\n#\n");
+    for i in 0..16 {
+        gpl.push_str(&format!("{:3} {:3} {:3}\tColor {}\n", 0, i, 255, i));
+    }
+    let palette = Palette::parse("test", &gpl).unwrap();
+    assert_eq!(palette.rgb(3), Rgb::new(0, 3, 255));
+
+    assert!(matches!(
+        Palette::parse("short", "#000000\n"),
+        Err(FontError::InvalidPalette(_))
+    ));
+    assert!(matches!(
+        Palette::parse("bad", "not a color\n"),
+        Err(FontError::InvalidPalette(_))
+    ));
+
+    assert_eq!(Rgb::from_hex("#12aBcD"), Some(Rgb::new(0x12, 0xAB, 0xCD)));
+    for bad in ["#+fffff", "+fffff", "#ff ff0", "#fffff", "#ggffff"] {
+        assert_eq!(Rgb::from_hex(bad), None, "{bad}");
+    }
+    let typo = "#+f+f+f\n".repeat(16);
+    assert!(matches!(
+        Palette::parse("typo", &typo),
+        Err(FontError::InvalidPalette(_))
+    ));
+}
+
+#[test]
+fn ansi_output_uses_palette() {
+    let canvas = Canvas::render_text(&red_font(), "A", &RenderOptions::default()).unwrap();
+    let c64 = to_ansi_string(&canvas, &Palette::c64());
+    assert!(c64.starts_with("\x1B[38;2;104;55;43m"));
+
+    let options = OutputOptions {
+        palette: Palette::amiga_workbench(),
+        ..OutputOptions::new(OutputFormat::Ansi)
+    };
+    let out = String::from_utf8(render_canvas(&canvas, &options).bytes).unwrap();
+    assert!(out.starts_with("\x1B[38;2;255;136;0m"));
+}
+
+#[test]
+fn remap_turns_red_green() {
+    let opts = RenderOptions::default().remap_color(4, 2);
+    let canvas = Canvas::render_text(&red_font(), "A", &opts).unwrap();
+    let cell = canvas.get(0, 0).unwrap();
+    assert_eq!(cell.fg, Some(2));
+    assert_eq!(cell.bg, Some(12));
+
+    let opts = RenderOptions::default().remap_color(12, 10);
+    let canvas = Canvas::render_text(&red_font(), "A", &opts).unwrap();
+    assert_eq!(canvas.get(0, 0).unwrap().bg, Some(10));
+}
+
+#[test]
+fn parse_colors() {
+    assert_eq!(Color::parse("12"), Some(Color::Index(12)));
+    assert_eq!(Color::parse("16"), None);
+    assert_eq!(Color::parse("Light-Red"), Some(Color::Index(12)));
+    assert_eq!(Color::parse("lightgrey"), Some(Color::Index(7)));
+    assert_eq!(Color::parse("dark_gray"), Some(Color::Index(8)));
+    assert_eq!(
+        Color::parse("#102030"),
+        Some(Color::Rgb(Rgb::new(0x10, 0x20, 0x30)))
+    );
+    assert_eq!(Color::parse("purple"), None);
+}
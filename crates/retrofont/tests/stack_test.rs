@@ -0,0 +1,83 @@
+use retrofont::{
+    figlet::FigletFont,
+    tdf::{TdfFont, TdfFontType},
+    test_support::glyph,
+    Canvas, Font, FontError, FontStack, RenderOptions, VerticalAlign,
+};
+
+fn font(glyphs: &[(char, &[&str])]) -> Font {
+    let mut font = TdfFont::new("TEST", TdfFontType::Block, 1);
+    for (ch, rows) in glyphs {
+        font.add_glyph(*ch, glyph(rows));
+    }
+    Font::Tdf(font)
+}
+
+/// Capitals only, 4 rows.
+fn primary() -> Font {
+    font(&[('A', &["A", "A", "A", "A"]), ('B', &["B", "B", "B", "B"])])
+}
+
+/// Capitals and digits, 2 rows.
+fn fallback() -> Font {
+    font(&[('A', &["a", "a"]), ('B', &["b", "b"]), ('1', &["1", "1"])])
+}
+
+fn text(canvas: &Canvas) -> Vec<String> {
+    canvas
+        .lines()
+        .iter()
+        .map(|line| line.iter().map(|c| c.map_or(' ', |c| c.ch)).collect())
+        .collect()
+}
+
+#[test]
+fn characters_come_from_the_first_font_that_has_them() {
+    let stack = FontStack::new(primary()).with_fallback(fallback());
+    assert_eq!(stack.fonts().len(), 2);
+    assert_eq!(stack.font_for('A'), Some((0, 'A')));
+    // The primary font's case fallback wins over the fallback fonts.
+    assert_eq!(stack.font_for('a'), Some((0, 'A')));
+    assert_eq!(stack.font_for('1'), Some((1, '1')));
+    assert_eq!(stack.font_for('#'), None);
+
+    let options = RenderOptions::default();
+    let canvas = Canvas::render_stack(&stack, "a1", &options).unwrap();
+    assert_eq!(text(&canvas), ["A1", "A1", "A", "A"]);
+    assert!(matches!(
+        Canvas::render_stack(&stack, "A#", &options),
+        Err(FontError::UnknownChar('#'))
+    ));
+}
+
+#[test]
+fn fallback_glyphs_align_and_scale() {
+    let options = RenderOptions::default().align(VerticalAlign::Baseline);
+    let mut stack = FontStack::new(primary()).with_fallback(fallback());
+    let canvas = Canvas::render_stack(&stack, "A1", &options).unwrap();
+    assert_eq!(text(&canvas), ["A", "A", "A1", "A1"]);
+
+    // Cap heights 4 and 2: fallback glyphs are doubled.
+    stack.scale_fallbacks = true;
+    let one = stack.glyph('1').unwrap();
+    assert_eq!((one.width, one.height), (2, 4));
+    let canvas = Canvas::render_stack(&stack, "A1", &options).unwrap();
+    assert_eq!(text(&canvas), ["A11"; 4]);
+}
+
+#[test]
+fn tdf_borrows_punctuation_from_figlet() {
+    let coder = TdfFont::load(include_bytes!("tdf/CODERX.TDF"))
+        .unwrap()
+        .remove(0);
+    assert!(!coder.has_char('.'));
+    let doom = FigletFont::load(include_bytes!("figlet/doom.flf")).unwrap();
+    let stack = FontStack::new(Font::Tdf(coder)).with_fallback(Font::Figlet(doom));
+    let options = RenderOptions::default().align(VerticalAlign::Baseline);
+    let canvas = Canvas::render_stack(&stack, "A.", &options).unwrap();
+    // The dot sits on the bottom row of the capitals (baseline 8); the blank descender rows
+    // of the FIGlet glyph hang below.
+    let rows = text(&canvas);
+    assert_eq!(rows.len(), 10);
+    assert!(rows[7].ends_with("(_)"));
+}
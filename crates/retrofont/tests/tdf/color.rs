@@ -0,0 +1,117 @@
+use retrofont::{
+    tdf::{TdfFont, TdfFontType},
+    test_support::MemoryBufferTarget,
+    Font, Glyph, GlyphPart, RenderOptions,
+};
+
+const TEST_FONT: &[u8] = include_bytes!("CODERX.TDF");
+
+fn attributed_font() -> TdfFont {
+    let mut font = TdfFont::new("FILL", TdfFontType::Color, 0);
+    font.add_glyph(
+        'A',
+        Glyph {
+            width: 3,
+            height: 1,
+            parts: vec![
+                GlyphPart::AnsiChar {
+                    ch: ' ',
+                    fg: 0x7,
+                    bg: 0x1,
+                    blink: false,
+                },
+                GlyphPart::AnsiChar {
+                    ch: '\u{a0}',
+                    fg: 0xE,
+                    bg: 0x4,
+                    blink: false,
+                },
+                GlyphPart::Skip,
+            ],
+        },
+    );
+    font
+}
+
+#[test]
+fn tdf_color_space_keeps_attribute() {
+    let bytes = attributed_font().to_bytes().unwrap();
+    let parsed = TdfFont::load(&bytes).unwrap();
+    let glyph = parsed[0].glyph('A').unwrap();
+    assert_eq!(glyph.parts, attributed_font().glyph('A').unwrap().parts);
+
+    let mut target = MemoryBufferTarget::new();
+    Font::Tdf(parsed[0].clone())
+        .render_glyph(&mut target, 'A', &RenderOptions::default())
+        .unwrap();
+    let cells = &target.lines[0];
+    assert_eq!(cells[0].ch, ' ');
+    assert_eq!(cells[0].bg, Some(0x1));
+    // Hard blanks render as visible spaces with their colors
+    assert_eq!(cells[1].ch, ' ');
+    assert_eq!(cells[1].bg, Some(0x4));
+}
+
+#[test]
+fn tdf_color_glyph_data_round_trips_exactly() {
+    let bytes = attributed_font().to_bytes().unwrap();
+    let reparsed = TdfFont::load(&bytes).unwrap();
+    assert_eq!(reparsed[0].to_bytes().unwrap(), bytes);
+
+    let fonts = TdfFont::load(TEST_FONT).unwrap();
+    let bundle = TdfFont::serialize_bundle(&fonts).unwrap();
+    let parsed = TdfFont::load(&bundle).unwrap();
+    for (a, b) in fonts.iter().zip(parsed.iter()) {
+        for (ch, glyph) in a.iter_glyphs() {
+            assert_eq!(glyph.parts, b.glyph(ch).unwrap().parts, "glyph {ch:?}");
+        }
+    }
+    assert_eq!(TdfFont::serialize_bundle(&parsed).unwrap(), bundle);
+}
+
+fn blink_font() -> TdfFont {
+    let mut font = TdfFont::new("ICE", TdfFontType::Color, 0);
+    font.add_glyph(
+        'I',
+        Glyph {
+            width: 2,
+            height: 1,
+            parts: vec![
+                GlyphPart::AnsiChar {
+                    ch: 'I',
+                    fg: 0xF,
+                    bg: 0x4,
+                    blink: true,
+                },
+                GlyphPart::AnsiChar {
+                    ch: 'C',
+                    fg: 0x0,
+                    bg: 0xC,
+                    blink: false,
+                },
+            ],
+        },
+    );
+    font
+}
+
+#[test]
+fn tdf_ice_colors_use_blink_bit_as_bright_background() {
+    let bytes = blink_font().to_bytes().unwrap();
+    let font = Font::Tdf(TdfFont::load(&bytes).unwrap().remove(0));
+
+    let mut blink = MemoryBufferTarget::new();
+    font.render_glyph(&mut blink, 'I', &RenderOptions::default())
+        .unwrap();
+    assert_eq!(blink.lines[0][0].bg, Some(0x4));
+    assert!(blink.lines[0][0].blink);
+
+    let options = RenderOptions::default().ice_colors(true);
+    let mut ice = MemoryBufferTarget::new();
+    font.render_glyph(&mut ice, 'I', &options).unwrap();
+    assert_eq!(ice.lines[0][0].bg, Some(0xC));
+    assert!(!ice.lines[0][0].blink);
+    // A bright background written by the TDF writer survives the round trip
+    assert_eq!(ice.lines[0][1].bg, Some(0xC));
+    assert_eq!(ice.lines[0][1].attribute(), 0xC0);
+}
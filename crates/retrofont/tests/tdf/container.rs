@@ -1,4 +1,8 @@
-use retrofont::tdf::{TdfFont, TdfFontType};
+use retrofont::{
+    bundle::TdfBundle,
+    tdf::{TdfFont, TdfFontType},
+    FontError,
+};
 
 const TEST_FONT: &[u8] = include_bytes!("CODERX.TDF");
 
@@ -27,3 +31,162 @@ fn test_save_and_reload_bundle() {
         assert_eq!(a.name, b.name);
     }
 }
+
+fn with_sauce(data: &[u8]) -> Vec<u8> {
+    let mut out = data.to_vec();
+    out.push(0x1A);
+    let mut sauce = b"SAUCE00".to_vec();
+    for (text, len) in [
+        ("Coder fonts", 35),
+        ("Coder", 20),
+        ("Group", 20),
+        ("19960618", 8),
+    ] {
+        let mut field = text.as_bytes().to_vec();
+        field.resize(len, b' ');
+        sauce.extend(field);
+    }
+    sauce.resize(128, 0);
+    out.extend(sauce);
+    out
+}
+
+#[test]
+fn test_bundle_editing_keeps_sauce() {
+    let mut bundle = TdfBundle::load(&with_sauce(TEST_FONT)).unwrap();
+    assert_eq!(bundle.len(), 6);
+    let sauce = bundle.sauce().unwrap();
+    assert_eq!(sauce.title, "Coder fonts");
+    assert_eq!(sauce.date, "19960618");
+
+    assert_eq!(bundle.position("coder red"), Some(4));
+    bundle.move_font(4, 0).unwrap();
+    bundle.rename(0, "Red").unwrap();
+    assert!(bundle.rename(0, "A name that is too long").is_err());
+    let silver = bundle.remove(5).unwrap();
+    assert_eq!(silver.name, "Coder Silver");
+    bundle.replace(1, silver).unwrap();
+    assert!(bundle.remove(9).is_err());
+
+    let mut other = TdfBundle::new();
+    other.add(TdfFont::new("Empty", TdfFontType::Block, 1));
+    bundle.merge(other);
+
+    let reloaded = TdfBundle::load(&bundle.to_bytes().unwrap()).unwrap();
+    let names: Vec<&str> = reloaded.fonts().iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "Red",
+            "Coder Silver",
+            "Coder Green",
+            "Coder Margen",
+            "Coder Purple",
+            "Empty"
+        ]
+    );
+    assert_eq!(reloaded.sauce(), Some(sauce.clone()));
+    assert_eq!(
+        reloaded.by_name("Red").unwrap().glyph('A').unwrap().parts,
+        TdfFont::load(TEST_FONT).unwrap()[4]
+            .glyph('A')
+            .unwrap()
+            .parts
+    );
+
+    let parts = reloaded.split();
+    assert_eq!(parts.len(), 6);
+    assert_eq!(parts[1].fonts()[0].name, "Coder Silver");
+    assert_eq!(parts[1].sauce(), Some(sauce));
+}
+
+#[test]
+fn test_recover_damaged_bundle() {
+    let indicator = 0xFF00_AA55u32.to_le_bytes();
+    let starts: Vec<usize> = TEST_FONT
+        .windows(4)
+        .enumerate()
+        .filter(|(_, w)| *w == indicator)
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(starts.len(), 6);
+
+    let mut data = TEST_FONT[..starts[2]].to_vec();
+    let garbage = data.len();
+    data.extend(b"line noise");
+    let third = data.len();
+    data.extend(&TEST_FONT[starts[2]..starts[3]]);
+    let fourth = data.len();
+    data.extend(&TEST_FONT[starts[3]..starts[4]]);
+    data[fourth + 4 + 13 + 4] = 9; // font type
+    data.extend(&TEST_FONT[starts[4]..]);
+    data.truncate(data.len() - 500);
+    assert!(TdfFont::load(&data).is_err());
+
+    let recovered = TdfFont::recover(&data);
+    let names: Vec<&str> = recovered.fonts.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "Coder Blue",
+            "Coder Green",
+            "Coder Margen",
+            "Coder Red",
+            "Coder Silver"
+        ]
+    );
+    assert_eq!(recovered.truncated, [4]);
+    assert_eq!(recovered.skipped[0].range, garbage..third);
+    assert_eq!(recovered.skipped[1].range.start, fourth);
+    assert!(matches!(
+        recovered.skipped[1].reason,
+        FontError::TdfUnsupportedType { font_type: 9, .. }
+    ));
+
+    // The cut off font keeps the glyphs that start before the end of the data.
+    assert!(recovered.skipped[2..]
+        .iter()
+        .all(|s| matches!(s.reason, FontError::TdfGlyphOutOfBounds { .. })));
+    let silver = &recovered.fonts[4];
+    assert!(silver.glyph('A').is_some());
+    assert!(silver.glyph_count() < 63);
+}
+
+#[test]
+fn test_recover_reports_trailing_garbage() {
+    for data in [TEST_FONT.to_vec(), with_sauce(TEST_FONT)] {
+        let recovered = TdfFont::recover(&data);
+        assert_eq!(recovered.fonts.len(), 6);
+        assert!(recovered.skipped.is_empty());
+    }
+
+    let mut data = TEST_FONT.to_vec();
+    data.extend(b"line noise");
+    let recovered = TdfFont::recover(&data);
+    assert_eq!(recovered.fonts.len(), 6);
+    assert_eq!(recovered.skipped.len(), 1);
+    let end = data.len() - 10;
+    assert_eq!(recovered.skipped[0].range, end..data.len());
+    assert!(matches!(
+        recovered.skipped[0].reason,
+        FontError::TdfTrailingBytes { offset, len: 10 } if offset == end
+    ));
+}
+
+#[test]
+fn test_errors_name_font_and_offset() {
+    let mut data = TEST_FONT.to_vec();
+    // Font type of the third font
+    let offset = 14858 + 4 + 13 + 4;
+    data[offset] = 9;
+    let err = TdfFont::load(&data).err().unwrap();
+    assert!(matches!(err, FontError::TdfBundleFont { index: 2, .. }));
+    assert!(matches!(
+        err.root(),
+        FontError::TdfUnsupportedType { font_type: 9, offset: o } if *o == offset
+    ));
+    assert_eq!(
+        err.to_string(),
+        format!("TDF font #3: TDF: unsupported font type 9 at byte {offset}")
+    );
+}
@@ -1,7 +1,8 @@
 use retrofont::{
+    cp437::{transliterate, Cp437Options, UnmappablePolicy},
     tdf::{TdfFont, TdfFontType},
     test_support::MemoryBufferTarget,
-    Font, Glyph, GlyphPart, RenderOptions,
+    Font, FontError, Glyph, GlyphPart, RenderOptions,
 };
 
 fn lines(buf: &MemoryBufferTarget) -> Vec<String> {
@@ -159,7 +160,7 @@ fn tdf_bundle_multiple_fonts() {
     let bundle = TdfFont::serialize_bundle(&[f1, f2]).unwrap();
 
     // Write bundle for manual inspection testing
-    std::fs::write("test_bundle.tdf", &bundle).ok();
+    std::fs::write(std::env::temp_dir().join("test_bundle.tdf"), &bundle).ok();
 
     let parsed = TdfFont::load(&bundle).unwrap();
     assert_eq!(parsed.len(), 2);
@@ -254,3 +255,117 @@ fn tdf_outline_uses_unicode_box_chars() {
         .unwrap();
     assert_eq!(lines(&target)[0], "──│");
 }
+
+#[test]
+fn tdf_save_transliterates_to_cp437() {
+    let mut font = TdfFont::new("UNI", TdfFontType::Block, 0);
+    font.add_glyph(
+        'A',
+        Glyph {
+            width: 4,
+            height: 1,
+            parts: "╭▛’€".chars().map(GlyphPart::Char).collect(),
+        },
+    );
+
+    // Default: nearest equivalents, '?' for the rest
+    let fonts = TdfFont::load(&font.to_bytes().unwrap()).unwrap();
+    let row: String = fonts[0]
+        .glyph('A')
+        .unwrap()
+        .parts
+        .iter()
+        .map(|p| match p {
+            GlyphPart::Char(c) => *c,
+            other => panic!("unexpected part {other:?}"),
+        })
+        .collect();
+    assert_eq!(row, "┌█'?");
+
+    let saved = font
+        .to_bytes_with(Cp437Options {
+            policy: UnmappablePolicy::Report,
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(saved.transliterated, vec!['╭', '▛', '’']);
+    assert_eq!(saved.unmappable, vec!['€']);
+
+    assert!(font
+        .to_bytes_with(Cp437Options {
+            policy: UnmappablePolicy::Error,
+            ..Default::default()
+        })
+        .is_err());
+    // Without transliteration only exact matches pass
+    let saved = font
+        .to_bytes_with(Cp437Options {
+            transliterate: false,
+            policy: UnmappablePolicy::Report,
+        })
+        .unwrap();
+    assert_eq!(saved.unmappable, vec!['╭', '▛', '’', '€']);
+
+    assert_eq!(transliterate('ł'), Some('l'));
+    assert_eq!(transliterate('Ê'), Some('E'));
+    assert_eq!(transliterate('┌'), None);
+}
+
+#[test]
+fn tdf_try_glyph_reports_corrupt_data() {
+    // Glyph block of a single font: header (20), record (25), lookup table (188)
+    const GLYPH_BLOCK: usize = 20 + 25 + 188;
+    let mut font = TdfFont::new("COLOR", TdfFontType::Color, 1);
+    font.add_glyph(
+        'A',
+        Glyph {
+            width: 2,
+            height: 1,
+            parts: vec![GlyphPart::Char('A'), GlyphPart::Char('B')],
+        },
+    );
+    let bytes = font.to_bytes().unwrap();
+    let fonts = TdfFont::load_checked(&bytes).unwrap();
+    assert!(fonts[0].try_glyph('A').unwrap().is_some());
+    assert!(fonts[0].try_glyph('B').unwrap().is_none());
+
+    let mut wrong_size = bytes.clone();
+    wrong_size[GLYPH_BLOCK] = 3;
+    let fonts = TdfFont::load(&wrong_size).unwrap();
+    assert!(matches!(
+        fonts[0].try_glyph('A'),
+        Err(FontError::TdfGlyphSizeMismatch {
+            ch: 'A',
+            declared: (3, 1),
+            actual: (2, 1)
+        })
+    ));
+    // The lenient path still renders the glyph.
+    assert_eq!(fonts[0].glyph('A').unwrap().parts.len(), 2);
+    assert!(matches!(
+        TdfFont::load_checked(&wrong_size),
+        Err(FontError::TdfBundleFont { index: 0, .. })
+    ));
+
+    // Cut off inside the second cell's attribute, and without terminator.
+    let mut truncated = bytes[..GLYPH_BLOCK + 5].to_vec();
+    truncated[20 + 23] = 5; // block size
+    let fonts = TdfFont::load(&truncated).unwrap();
+    assert!(matches!(
+        fonts[0].try_glyph('A'),
+        Err(FontError::TdfGlyphTruncated { ch: 'A', offset }) if offset == GLYPH_BLOCK + 4
+    ));
+    let mut unterminated = bytes[..GLYPH_BLOCK + 6].to_vec();
+    unterminated[20 + 23] = 6;
+    let fonts = TdfFont::load(&unterminated).unwrap();
+    assert!(matches!(
+        fonts[0].try_glyph('A'),
+        Err(FontError::TdfGlyphUnterminated { ch: 'A', .. })
+    ));
+}
+
+#[test]
+fn tdf_shipped_fonts_decode_cleanly() {
+    let fonts = TdfFont::load_checked(include_bytes!("tdf/CODERX.TDF")).unwrap();
+    assert_eq!(fonts.len(), 6);
+}
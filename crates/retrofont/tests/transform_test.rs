@@ -0,0 +1,138 @@
+use retrofont::{
+    figlet::FigletFont,
+    tdf::{TdfFont, TdfFontType},
+    test_support::glyph,
+    transform::{transform_figlet, transform_glyph, transform_tdf, Transform},
+    FontError, Glyph, GlyphGrid, GlyphPart,
+};
+
+fn text(glyph: &Glyph) -> Vec<String> {
+    GlyphGrid::from(glyph)
+        .rows()
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|p| match p {
+                    GlyphPart::Skip => '.',
+                    GlyphPart::Char(c) => *c,
+                    GlyphPart::FillMarker => '@',
+                    GlyphPart::OutlinePlaceholder(b) => (*b).into(),
+                    _ => '?',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn flips_swap_directional_chars() {
+    let source = glyph(&["┌─▌/", "╚═▄▛"]);
+    let flipped = transform_glyph(&source, Transform::FlipHorizontal);
+    assert_eq!(text(&flipped), ["\\▐─┐", "▜▄═╝"]);
+    assert_eq!(
+        transform_glyph(&flipped, Transform::FlipHorizontal).parts,
+        source.parts
+    );
+
+    let flipped = transform_glyph(&source, Transform::FlipVertical);
+    assert_eq!(text(&flipped), ["╔═▀▙", "└─▌\\"]);
+    assert_eq!(
+        transform_glyph(&flipped, Transform::FlipVertical).parts,
+        source.parts
+    );
+}
+
+#[test]
+fn rotate_turns_cells_and_chars() {
+    let rotated = transform_glyph(&glyph(&["┌─▀", "│.x"]), Transform::Rotate90);
+    assert_eq!((rotated.width, rotated.height), (2, 3));
+    assert_eq!(text(&rotated), ["─┐", ".│", "x▐"]);
+
+    // Four quarter turns are the identity.
+    let source = glyph(&["╔╗▗", "╚╝/"]);
+    let mut turned = source.clone();
+    for _ in 0..4 {
+        turned = transform_glyph(&turned, Transform::Rotate90);
+    }
+    assert_eq!(turned.parts, source.parts);
+}
+
+#[test]
+fn scale_stretches_lines_and_blocks() {
+    let wide = transform_glyph(&glyph(&["┌┐", "▌x"]), Transform::ScaleX2);
+    assert_eq!(text(&wide), ["┌──┐", "█.xx"]);
+
+    let tall = transform_glyph(&glyph(&["┌▀", "└▟"]), Transform::ScaleY2);
+    assert_eq!(text(&tall), ["┌█", "│.", "│▐", "└█"]);
+}
+
+#[test]
+fn italic_slants_rows() {
+    let slanted = transform_glyph(&glyph(&["A", "B", "C", "D", "E"]), Transform::Italic);
+    assert_eq!(text(&slanted), ["..A", ".B.", ".C.", "D..", "E.."]);
+}
+
+#[test]
+fn outline_placeholders_follow_edges() {
+    // Top-left corner of an outline glyph.
+    let parts = vec![
+        GlyphPart::OutlinePlaceholder(b'E'),
+        GlyphPart::OutlinePlaceholder(b'A'),
+        GlyphPart::NewLine,
+        GlyphPart::OutlinePlaceholder(b'C'),
+        GlyphPart::FillMarker,
+    ];
+    let corner = Glyph {
+        width: 2,
+        height: 2,
+        parts,
+    };
+    assert_eq!(
+        text(&transform_glyph(&corner, Transform::FlipHorizontal)),
+        ["AF", "@D"]
+    );
+    assert_eq!(
+        text(&transform_glyph(&corner, Transform::FlipVertical)),
+        ["C@", "KB"]
+    );
+    assert_eq!(
+        text(&transform_glyph(&corner, Transform::Rotate90)),
+        ["AF", "@D"]
+    );
+    assert_eq!(
+        text(&transform_glyph(&corner, Transform::ScaleX2)),
+        ["EAAA", "C@@@"]
+    );
+}
+
+#[test]
+fn transform_whole_fonts() {
+    let mut tdf = TdfFont::new("TEST", TdfFontType::Block, 1);
+    tdf.add_glyph('A', glyph(&["▌"]));
+    tdf.add_glyph('B', glyph(&["┌", "└"]));
+    let wide = transform_tdf(&tdf, Transform::ScaleX2).unwrap();
+    assert_eq!(wide.spacing, 2);
+    assert_eq!(wide.glyph_count(), 2);
+    assert_eq!(text(wide.glyph('A').unwrap()), ["█."]);
+    assert_eq!(text(wide.glyph('B').unwrap()), ["┌─", "└─"]);
+
+    // 16 cells doubled don't fit into the 30 TDF allows
+    tdf.add_glyph('C', glyph(&["████████████████"]));
+    assert!(matches!(
+        transform_tdf(&tdf, Transform::ScaleX2),
+        Err(FontError::TdfGlyphTooLarge {
+            ch: 'C',
+            width: 32,
+            height: 1
+        })
+    ));
+    assert!(transform_tdf(&tdf, Transform::FlipHorizontal).is_ok());
+
+    let doom = FigletFont::load(include_bytes!("figlet/doom.flf")).unwrap();
+    let mirrored = transform_figlet(&doom, Transform::FlipHorizontal);
+    assert_eq!(mirrored.glyph_count(), doom.glyph_count());
+    let (a, b) = (doom.glyph('A').unwrap(), mirrored.glyph('A').unwrap());
+    assert_eq!((a.width, a.height), (b.width, b.height));
+    let tall = transform_figlet(&doom, Transform::ScaleY2);
+    assert_eq!(tall.glyph('A').unwrap().height, a.height * 2);
+}
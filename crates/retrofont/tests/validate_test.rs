@@ -0,0 +1,162 @@
+use retrofont::{
+    figlet::FigletFont,
+    tdf::{TdfFont, TdfFontType},
+    test_support::glyph,
+    validate::{validate, Issue},
+    Glyph, GlyphPart,
+};
+
+// Header (20) + indicator (4) + name (13) + magic (4)
+const TYPE_OFFSET: usize = 41;
+// Type, spacing, block size and the 94 entry lookup table
+const GLYPH_BLOCK: usize = TYPE_OFFSET + 4 + 94 * 2;
+
+fn issues(bytes: &[u8]) -> Vec<(Option<char>, Option<usize>, Issue)> {
+    validate(bytes)
+        .unwrap()
+        .into_iter()
+        .map(|d| (d.ch, d.offset, d.issue))
+        .collect()
+}
+
+#[test]
+fn shipped_fonts_are_clean() {
+    assert!(validate(include_bytes!("tdf/CODERX.TDF"))
+        .unwrap()
+        .is_empty());
+    assert!(validate(include_bytes!("figlet/doom.flf"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn tdf_declared_size_and_trailing_bytes() {
+    let mut font = TdfFont::new("TEST", TdfFontType::Block, 1);
+    font.add_glyph('A', glyph(&["AB", "CD"]));
+    let mut bytes = TdfFont::serialize_bundle(&[font]).unwrap();
+    assert_eq!(issues(&bytes), vec![]);
+
+    bytes[GLYPH_BLOCK] = 5;
+    bytes[GLYPH_BLOCK + 1] = 3;
+    bytes.extend(b"junk");
+    let found = issues(&bytes);
+    assert_eq!(
+        found,
+        vec![
+            (
+                Some('A'),
+                Some(GLYPH_BLOCK),
+                Issue::WidthMismatch {
+                    declared: 5,
+                    actual: 2
+                }
+            ),
+            (
+                Some('A'),
+                Some(GLYPH_BLOCK + 1),
+                Issue::HeightMismatch {
+                    declared: 3,
+                    actual: 2
+                }
+            ),
+            (None, Some(bytes.len() - 4), Issue::TrailingBytes(4)),
+        ]
+    );
+    assert!(validate(&bytes).unwrap()[0]
+        .to_string()
+        .starts_with("warning [font #1] 'A' @"));
+}
+
+#[test]
+fn tdf_ragged_rows_and_outline_letters() {
+    let mut font = TdfFont::new("OUT", TdfFontType::Outline, 1);
+    font.add_glyph('A', glyph(&["AB", "CDZ"]));
+    let bytes = font.to_bytes().unwrap();
+    let found = issues(&bytes);
+    assert!(found.contains(&(
+        Some('A'),
+        Some(GLYPH_BLOCK + 2 + 5),
+        Issue::InvalidOutlineChar(b'Z')
+    )));
+    assert!(found
+        .iter()
+        .any(|(_, _, i)| *i == Issue::RaggedRows { min: 2, max: 3 }));
+}
+
+#[test]
+fn tdf_attributes_in_block_font() {
+    let mut font = TdfFont::new("COLOR", TdfFontType::Color, 1);
+    font.add_glyph('A', glyph(&["AB"]));
+    let mut bytes = font.to_bytes().unwrap();
+    bytes[TYPE_OFFSET] = 1;
+    assert_eq!(
+        issues(&bytes),
+        vec![(Some('A'), Some(GLYPH_BLOCK), Issue::AttributesInBlockFont)]
+    );
+
+    // Colors added to a Block font in memory are reported without an offset.
+    let mut font = TdfFont::new("BLOCK", TdfFontType::Block, 1);
+    font.add_glyph(
+        'B',
+        Glyph {
+            width: 1,
+            height: 1,
+            parts: vec![GlyphPart::AnsiChar {
+                ch: 'B',
+                fg: 4,
+                bg: 0,
+                blink: false,
+            }],
+        },
+    );
+    let found = font.validate();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].issue, Issue::AttributesInBlockFont);
+    assert_eq!(found[0].offset, None);
+}
+
+#[test]
+fn tdf_overlapping_glyphs() {
+    let mut font = TdfFont::new("TEST", TdfFontType::Block, 1);
+    font.add_glyph('A', glyph(&["AB"]));
+    font.add_glyph('B', glyph(&["CD"]));
+    let mut bytes = font.to_bytes().unwrap();
+    // Point 'B' into the middle of 'A'.
+    let lookup_b = TYPE_OFFSET + 4 + (b'B' - b'!') as usize * 2;
+    bytes[lookup_b] = 1;
+    let found = issues(&bytes);
+    assert!(found.contains(&(
+        Some('B'),
+        Some(GLYPH_BLOCK + 1),
+        Issue::OverlappingGlyphs('A')
+    )));
+}
+
+#[test]
+fn figlet_missing_and_ragged_glyphs() {
+    let mut font = FigletFont::new("test");
+    for ch in 32u8..=126 {
+        if ch != b'~' {
+            font.add_raw_char(ch, &["ab", "cd"]);
+        }
+    }
+    font.add_raw_char(b'x', &["ab", "c"]);
+    let found = font.validate();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].ch, Some('~'));
+    assert_eq!(found[0].issue, Issue::MissingChar);
+    assert_eq!(found[1].ch, Some('x'));
+    assert_eq!(found[1].issue, Issue::RaggedRows { min: 1, max: 2 });
+
+    let flf = b"flf2a$ 3 2 10 0 0\n a@\n b@@\n";
+    let found = validate(flf).unwrap();
+    let space = found.iter().find(|d| d.ch == Some(' ')).unwrap();
+    assert_eq!(
+        space.issue,
+        Issue::HeightMismatch {
+            declared: 3,
+            actual: 2
+        }
+    );
+    assert_eq!(space.offset, Some(18));
+}